@@ -1,11 +1,12 @@
 use either::Either;
-use futures::Stream;
+use futures::{Stream, TryStreamExt};
 use serde::{de::DeserializeOwned, Serialize};
-use std::fmt::Debug;
+use std::{fmt::Debug, pin::pin, time::Duration};
 
 use crate::{api::Api, Error, Result};
 use kube_core::{
-    metadata::PartialObjectMeta, object::ObjectList, params::*, response::Status, ErrorResponse, WatchEvent,
+    dynamic::DynamicObject, metadata::PartialObjectMeta, object::ObjectList, params::*, response::Status,
+    ErrorResponse, WatchEvent,
 };
 
 /// PUSH/PUT/POST/GET abstractions
@@ -568,3 +569,181 @@ where
         self.client.request_events::<PartialObjectMeta<K>>(req).await
     }
 }
+
+/// Waiting helpers for dynamic objects
+impl Api<DynamicObject> {
+    /// Watch a named object until `cond` holds for it, or until `timeout` elapses
+    ///
+    /// Resolves to the first version of the object satisfying `cond`, or `None` on timeout.
+    /// The object's current state is checked first, and the watch is re-established if the apiserver closes it,
+    /// starting over from the current state when the last seen resource version has expired.
+    /// Deletions of the object are ignored, as it may be recreated.
+    ///
+    /// ```no_run
+    /// use kube::{api::{Api, DynamicObject}, discovery::ApiResource};
+    /// use std::time::Duration;
+    ///
+    /// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client: kube::Client = todo!();
+    /// # let ar: ApiResource = todo!();
+    /// let api: Api<DynamicObject> = Api::namespaced_with(client, "apps", &ar);
+    /// let ready = |obj: &DynamicObject| obj.data["status"]["ready"] == true;
+    /// if let Some(obj) = api.wait_for_condition("blog", ready, Duration::from_secs(30)).await? {
+    ///     println!("ready: {:?}", obj.metadata.name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn wait_for_condition(
+        &self,
+        name: &str,
+        cond: impl Fn(&DynamicObject) -> bool,
+        timeout: Duration,
+    ) -> Result<Option<DynamicObject>> {
+        let wp = WatchParams::default().fields(&format!("metadata.name={name}"));
+        let wait = async {
+            // version 0 makes the apiserver replay the current state as an initial ADDED event
+            let mut version = "0".to_string();
+            loop {
+                let mut stream = pin!(self.watch(&wp, &version).await?);
+                while let Some(event) = stream.try_next().await? {
+                    match event {
+                        WatchEvent::Added(obj) | WatchEvent::Modified(obj) => {
+                            if cond(&obj) {
+                                return Ok(obj);
+                            }
+                            if let Some(rv) = obj.metadata.resource_version {
+                                version = rv;
+                            }
+                        }
+                        // a deleted object never satisfies the wait, but it may be recreated
+                        WatchEvent::Deleted(obj) => {
+                            if let Some(rv) = obj.metadata.resource_version {
+                                version = rv;
+                            }
+                        }
+                        WatchEvent::Bookmark(bm) => version = bm.metadata.resource_version,
+                        // resource version too old to resume from, start over from the current state
+                        WatchEvent::Error(err) if err.code == 410 => {
+                            version = "0".to_string();
+                            break;
+                        }
+                        WatchEvent::Error(err) => return Err(Error::Api(err)),
+                    }
+                }
+            }
+        };
+        match tokio::time::timeout(timeout, wait).await {
+            Ok(res) => res.map(Some),
+            Err(_elapsed) => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{client::Body, Api, Client};
+    use http::{Request, Response};
+    use kube_core::{
+        dynamic::{ApiResource, DynamicObject},
+        gvk::GroupVersionKind,
+    };
+    use std::{pin::pin, time::Duration};
+    use tower_test::mock;
+
+    #[tokio::test]
+    async fn wait_for_condition_resolves_on_matching_event() {
+        let (mock_service, handle) = mock::pair::<Request<Body>, Response<Body>>();
+        let spawned = tokio::spawn(async move {
+            let mut handle = pin!(handle);
+            let (request, send) = handle.next_request().await.expect("service not called");
+            assert_eq!(
+                request.uri().to_string(),
+                "/apis/clux.dev/v1/namespaces/default/foos?&watch=true&timeoutSeconds=290&fieldSelector=metadata.name%3Dbaz&allowWatchBookmarks=true&resourceVersion=0"
+            );
+            let event = |kind: &str, rv: &str, ready: bool| {
+                let obj = serde_json::json!({
+                    "apiVersion": "clux.dev/v1",
+                    "kind": "Foo",
+                    "metadata": { "name": "baz", "resourceVersion": rv },
+                    "status": { "ready": ready },
+                });
+                serde_json::json!({ "type": kind, "object": obj }).to_string() + "\n"
+            };
+            let body = [
+                event("ADDED", "1", false),
+                event("MODIFIED", "2", false),
+                event("MODIFIED", "3", true),
+            ]
+            .concat();
+            send.send_response(Response::builder().body(Body::from(body.into_bytes())).unwrap());
+        });
+
+        let ar = ApiResource::from_gvk(&GroupVersionKind::gvk("clux.dev", "v1", "Foo"));
+        let api: Api<DynamicObject> =
+            Api::namespaced_with(Client::new(mock_service, "default"), "default", &ar);
+        let ready = |obj: &DynamicObject| obj.data["status"]["ready"] == true;
+        let obj = api
+            .wait_for_condition("baz", ready, Duration::from_secs(5))
+            .await
+            .unwrap()
+            .expect("condition met before timeout");
+        assert_eq!(obj.metadata.resource_version.as_deref(), Some("3"));
+        spawned.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn wait_for_condition_rewatches_after_gone() {
+        let (mock_service, handle) = mock::pair::<Request<Body>, Response<Body>>();
+        let spawned = tokio::spawn(async move {
+            let mut handle = pin!(handle);
+            let event = |kind: &str, rv: &str, ready: bool| {
+                let obj = serde_json::json!({
+                    "apiVersion": "clux.dev/v1",
+                    "kind": "Foo",
+                    "metadata": { "name": "baz", "resourceVersion": rv },
+                    "status": { "ready": ready },
+                });
+                serde_json::json!({ "type": kind, "object": obj }).to_string() + "\n"
+            };
+            let gone = serde_json::json!({
+                "type": "ERROR",
+                "object": {
+                    "status": "Failure",
+                    "message": "too old resource version: 5 (10)",
+                    "reason": "Expired",
+                    "code": 410,
+                },
+            });
+            let gone = format!("{gone}\n");
+            let responses = [
+                ("0", event("ADDED", "5", false)),
+                // the server closed the watch, resuming from 5 fails
+                ("5", gone),
+                // a deletion is not a match, even if it satisfies the condition
+                (
+                    "0",
+                    [event("DELETED", "6", true), event("ADDED", "7", true)].concat(),
+                ),
+            ];
+            for (version, body) in responses {
+                let (request, send) = handle.next_request().await.expect("service not called");
+                let uri = request.uri().to_string();
+                assert!(uri.ends_with(&format!("&resourceVersion={version}")), "{uri}");
+                send.send_response(Response::builder().body(Body::from(body.into_bytes())).unwrap());
+            }
+        });
+
+        let ar = ApiResource::from_gvk(&GroupVersionKind::gvk("clux.dev", "v1", "Foo"));
+        let api: Api<DynamicObject> =
+            Api::namespaced_with(Client::new(mock_service, "default"), "default", &ar);
+        let ready = |obj: &DynamicObject| obj.data["status"]["ready"] == true;
+        let obj = api
+            .wait_for_condition("baz", ready, Duration::from_secs(5))
+            .await
+            .unwrap()
+            .expect("condition met before timeout");
+        assert_eq!(obj.metadata.resource_version.as_deref(), Some("7"));
+        spawned.await.unwrap();
+    }
+}
@@ -4,7 +4,9 @@ mod core_methods;
 #[cfg(feature = "ws")] mod remote_command;
 use std::fmt::Debug;
 
-#[cfg(feature = "ws")] pub use remote_command::{AttachedProcess, TerminalSize};
+#[cfg(feature = "ws")] pub use remote_command::{
+    collect_output, AttachedProcess, CollectedOutput, ExecTermination, TerminalSize,
+};
 #[cfg(feature = "ws")] mod portforward;
 #[cfg(feature = "ws")] pub use portforward::Portforwarder;
 
@@ -28,7 +30,7 @@ pub use kube_core::{
     gvk::{GroupVersionKind, GroupVersionResource},
     metadata::{ListMeta, ObjectMeta, PartialObjectMeta, PartialObjectMetaExt, TypeMeta},
     object::{NotUsed, Object, ObjectList},
-    request::Request,
+    request::{Request, TraceContext},
     watch::WatchEvent,
     Resource, ResourceExt,
 };
@@ -38,7 +40,7 @@ pub use params::{
     ValidationDirective, VersionMatch, WatchParams,
 };
 
-use crate::Client;
+use crate::{error::DiscoveryError, Client};
 /// The generic Api abstraction
 ///
 /// This abstracts over a [`Request`] and a type `K` so that
@@ -87,7 +89,9 @@ impl<K: Resource> Api<K> {
     where
         K: Resource<Scope = DynamicResourceScope>,
     {
-        // TODO: inspect dyntype scope to verify somehow?
+        if let Some(err) = scope_mismatch::<K>(&client, dyntype) {
+            tracing::warn!("using {} within namespace {}: {}", K::plural(dyntype), ns, err);
+        }
         let url = K::url_path(dyntype, Some(ns));
         Self {
             client,
@@ -112,6 +116,15 @@ impl<K: Resource> Api<K> {
         Self::namespaced_with(client, &ns, dyntype)
     }
 
+    /// Propagate a [`TraceContext`] on every request made through this `Api`
+    ///
+    /// This includes the WebSocket upgrades of `exec`, `attach` and `portforward`.
+    #[must_use]
+    pub fn with_trace_context(mut self, trace_context: TraceContext) -> Self {
+        self.request = self.request.with_trace_context(trace_context);
+        self
+    }
+
     /// Consume self and return the [`Client`]
     pub fn into_client(self) -> Client {
         self.into()
@@ -123,6 +136,17 @@ impl<K: Resource> Api<K> {
     }
 }
 
+/// The mismatch of using a resource within a namespace when the client's
+/// [`ScopeCache`](crate::discovery::ScopeCache) knows it to be cluster scoped
+pub(crate) fn scope_mismatch<K: Resource>(
+    client: &Client,
+    dyntype: &K::DynamicType,
+) -> Option<DiscoveryError> {
+    let cache = client.scope_cache()?;
+    let gvk = GroupVersionKind::gvk(&K::group(dyntype), &K::version(dyntype), &K::kind(dyntype));
+    cache.check_namespaced(&gvk).err()
+}
+
 /// Api constructors for Resource implementors with Default DynamicTypes
 ///
 /// This generally means structs implementing `k8s_openapi::Resource`.
@@ -247,7 +271,14 @@ impl<K> Debug for Api<K> {
 /// Sanity test on scope restrictions
 #[cfg(test)]
 mod test {
-    use crate::{client::Body, Api, Client};
+    use super::scope_mismatch;
+    use crate::{
+        client::Body,
+        core::{ApiResource, DynamicObject, GroupVersionKind},
+        discovery::{Scope, ScopeCache},
+        error::DiscoveryError,
+        Api, Client,
+    };
     use k8s_openapi::api::core::v1 as corev1;
 
     use http::{Request, Response};
@@ -263,4 +294,26 @@ mod test {
         let _: Api<corev1::PersistentVolume> = Api::all(client.clone());
         let _: Api<corev1::ConfigMap> = Api::namespaced(client, "default");
     }
+
+    #[tokio::test]
+    async fn namespaced_use_of_cluster_scoped_dynamic_resource_is_flagged() {
+        let (mock_service, _handle) = mock::pair::<Request<Body>, Response<Body>>();
+        let node = GroupVersionKind::gvk("", "v1", "Node");
+        let pod = GroupVersionKind::gvk("", "v1", "Pod");
+        let mut cache = ScopeCache::new();
+        cache.insert(node.clone(), Scope::Cluster);
+        cache.insert(pod.clone(), Scope::Namespaced);
+        let client = Client::new(mock_service, "default").with_scope_cache(cache);
+
+        let node_ar = ApiResource::from_gvk_with_plural(&node, "nodes");
+        let api: Api<DynamicObject> = Api::namespaced_with(client.clone(), "default", &node_ar);
+        assert!(matches!(
+            scope_mismatch::<DynamicObject>(&api.client, &node_ar),
+            Some(DiscoveryError::ScopeMismatch(gvk)) if gvk == "v1/Node"
+        ));
+
+        let pod_ar = ApiResource::from_gvk_with_plural(&pod, "pods");
+        let api: Api<DynamicObject> = Api::namespaced_with(client, "default", &pod_ar);
+        assert!(scope_mismatch::<DynamicObject>(&api.client, &pod_ar).is_none());
+    }
 }
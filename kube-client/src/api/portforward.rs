@@ -10,6 +10,8 @@ use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt, DuplexStream};
 use tokio_tungstenite::{tungstenite as ws, WebSocketStream};
 use tokio_util::io::ReaderStream;
 
+use crate::client::WsSessionPermit;
+
 /// Errors from Portforwarder.
 #[derive(Debug, Error)]
 pub enum Error {
@@ -89,7 +91,11 @@ pub struct Portforwarder {
 }
 
 impl Portforwarder {
-    pub(crate) fn new<S>(stream: WebSocketStream<S>, port_nums: &[u16]) -> Self
+    pub(crate) fn new<S>(
+        stream: WebSocketStream<S>,
+        port_nums: &[u16],
+        permit: Option<WsSessionPermit>,
+    ) -> Self
     where
         S: AsyncRead + AsyncWrite + Unpin + Sized + Send + 'static,
     {
@@ -106,12 +112,12 @@ impl Portforwarder {
             error_rxs.insert(*port, rx);
             error_txs.push(Some(tx));
         }
-        let task = tokio::spawn(start_message_loop(
-            stream,
-            port_nums.to_vec(),
-            task_ios,
-            error_txs,
-        ));
+        let message_loop = start_message_loop(stream, port_nums.to_vec(), task_ios, error_txs);
+        let task = tokio::spawn(async move {
+            // the session slot is held until the message loop ends
+            let _permit = permit;
+            message_loop.await
+        });
 
         Portforwarder {
             ports,
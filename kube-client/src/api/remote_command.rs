@@ -11,7 +11,7 @@ use futures::{
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tokio::{
-    io::{AsyncRead, AsyncWrite, AsyncWriteExt, DuplexStream},
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, DuplexStream},
     select,
 };
 use tokio_tungstenite::tungstenite::{self as ws};
@@ -87,6 +87,102 @@ pub enum Error {
 
 const MAX_BUF_SIZE: usize = 1024;
 
+/// How a process run through [`AttachedProcess`] terminated
+///
+/// Parsed from the `Status` sent on the error channel, see [`AttachedProcess::take_status`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(docsrs, doc(cfg(feature = "ws")))]
+pub enum ExecTermination {
+    /// The process exited with the given exit code
+    Exited(i32),
+    /// The process was killed by the given signal
+    Signaled(i32),
+}
+
+// Exit code of a process killed by the highest signal number, `SIGRTMAX` (64) on Linux
+const SIGNALED_MAX: i32 = 128 + 64;
+
+impl ExecTermination {
+    /// Parse the termination of a process from its exit `Status`
+    ///
+    /// A `Success` status is an exit code of 0. A `NonZeroExitCode` failure carries the exit code
+    /// in its `ExitCode` cause. The kubelet does not report signals directly, so this follows the
+    /// shell convention that exit codes above 128 mean the process was killed by signal `code - 128`,
+    /// e.g. 137 for `SIGKILL`, for codes within the signal range of 1 to 64. A process that deliberately
+    /// exits with such a code is indistinguishable.
+    ///
+    /// Returns `None` for any other status, such as a failure to start the process.
+    pub fn from_status(status: &Status) -> Option<Self> {
+        match (status.status.as_deref(), status.reason.as_deref()) {
+            (Some("Success"), _) => Some(Self::Exited(0)),
+            (Some("Failure"), Some("NonZeroExitCode")) => {
+                let code = status
+                    .details
+                    .as_ref()?
+                    .causes
+                    .as_ref()?
+                    .iter()
+                    .find(|c| c.reason.as_deref() == Some("ExitCode"))?
+                    .message
+                    .as_ref()?
+                    .parse::<i32>()
+                    .ok()?;
+                match code {
+                    129..=SIGNALED_MAX => Some(Self::Signaled(code - 128)),
+                    _ => Some(Self::Exited(code)),
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Output read from an attached process by [`collect_output`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(docsrs, doc(cfg(feature = "ws")))]
+pub struct CollectedOutput {
+    /// The bytes that were kept, at most the configured limit
+    pub data: Vec<u8>,
+    /// Whether output past the limit was discarded
+    pub truncated: bool,
+}
+
+/// Read an output stream of an [`AttachedProcess`] to the end, keeping at most `limit` bytes
+///
+/// A `limit` of `None` keeps everything, which is only safe for processes with bounded output.
+/// Output past the limit is still read, so that the process is never blocked on a full pipe,
+/// but it is discarded and [`CollectedOutput::truncated`] is set.
+///
+/// ```no_run
+/// # use kube_client::api::{collect_output, AttachedProcess};
+/// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+/// # let mut attached: AttachedProcess = todo!();
+/// let output = collect_output(attached.stdout().unwrap(), Some(1024 * 1024)).await?;
+/// if output.truncated {
+///     println!("stdout exceeded 1MiB");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "ws")))]
+pub async fn collect_output<R>(mut reader: R, limit: Option<usize>) -> std::io::Result<CollectedOutput>
+where
+    R: AsyncRead + Unpin,
+{
+    let limit = limit.unwrap_or(usize::MAX);
+    let mut output = CollectedOutput::default();
+    let mut buf = [0u8; MAX_BUF_SIZE];
+    loop {
+        let n = reader.read(&mut buf).await?;
+        if n == 0 {
+            return Ok(output);
+        }
+        let keep = n.min(limit - output.data.len());
+        output.data.extend_from_slice(&buf[..keep]);
+        output.truncated |= keep < n;
+    }
+}
+
 /// Represents an attached process in a container for [`attach`] and [`exec`].
 ///
 /// Provides access to `stdin`, `stdout`, and `stderr` if attached.
@@ -280,7 +376,9 @@ where
 {
     let mut stdin_stream = tokio_util::io::ReaderStream::new(stdin);
     let should_send_close = stream.supports_closing();
-    let (mut server_send, raw_server_recv) = stream.into_inner().split();
+    // the session slot is held until the message loop ends
+    let (stream, _permit) = stream.into_parts();
+    let (mut server_send, raw_server_recv) = stream.split();
     // Work with filtered messages to reduce noise.
     let mut server_recv = raw_server_recv.filter_map(filter_message).boxed();
     let mut have_terminal_size_rx = terminal_size_rx.is_some();
@@ -411,3 +509,74 @@ async fn filter_message(wsm: Result<ws::Message, ws::Error>) -> Option<Result<Me
         Err(err) => Some(Err(err)),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exit_status(code: i32) -> Status {
+        let status = if code == 0 {
+            serde_json::json!({ "metadata": {}, "status": "Success" })
+        } else {
+            serde_json::json!({
+                "metadata": {},
+                "status": "Failure",
+                "message": format!("command terminated with non-zero exit code: exit status {code}"),
+                "reason": "NonZeroExitCode",
+                "details": { "causes": [{ "reason": "ExitCode", "message": code.to_string() }] },
+            })
+        };
+        serde_json::from_value(status).unwrap()
+    }
+
+    #[tokio::test]
+    async fn collect_output_truncates_past_limit() {
+        let (mut tx, rx) = tokio::io::duplex(16);
+        let writer = tokio::spawn(async move {
+            tx.write_all(&[b'x'; 5000]).await.unwrap();
+        });
+        let output = collect_output(rx, Some(100)).await.unwrap();
+        writer.await.unwrap();
+        assert_eq!(output.data, [b'x'; 100]);
+        assert!(output.truncated);
+
+        let (mut tx, rx) = tokio::io::duplex(16);
+        tx.write_all(b"hello").await.unwrap();
+        drop(tx);
+        let output = collect_output(rx, None).await.unwrap();
+        assert_eq!(output.data, b"hello");
+        assert!(!output.truncated);
+    }
+
+    #[test]
+    fn exec_termination_from_status() {
+        assert_eq!(
+            ExecTermination::from_status(&exit_status(0)),
+            Some(ExecTermination::Exited(0))
+        );
+        assert_eq!(
+            ExecTermination::from_status(&exit_status(1)),
+            Some(ExecTermination::Exited(1))
+        );
+        assert_eq!(
+            ExecTermination::from_status(&exit_status(137)),
+            Some(ExecTermination::Signaled(9))
+        );
+        assert_eq!(
+            ExecTermination::from_status(&exit_status(128)),
+            Some(ExecTermination::Exited(128))
+        );
+        assert_eq!(
+            ExecTermination::from_status(&exit_status(255)),
+            Some(ExecTermination::Exited(255))
+        );
+
+        let internal: Status = serde_json::from_value(serde_json::json!({
+            "metadata": {},
+            "status": "Failure",
+            "reason": "InternalError",
+        }))
+        .unwrap();
+        assert_eq!(ExecTermination::from_status(&internal), None);
+    }
+}
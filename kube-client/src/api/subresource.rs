@@ -609,6 +609,6 @@ where
             .portforward(name, ports)
             .map_err(Error::BuildRequest)?;
         let stream = self.client.connect(req).await?;
-        Ok(Portforwarder::new(stream.into_inner(), ports))
+        Ok(stream.portforward(ports))
     }
 }
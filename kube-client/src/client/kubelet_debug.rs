@@ -70,7 +70,7 @@ impl Client {
             Request::kubelet_node_portforward(kubelet_params, ports).map_err(Error::BuildRequest)?;
         req.extensions_mut().insert("kubelet_node_portforward");
         let stream = self.connect(req).await?;
-        Ok(Portforwarder::new(stream, ports))
+        Ok(stream.portforward(ports))
     }
 
     /// Stream logs directly from node
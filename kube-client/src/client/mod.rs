@@ -17,6 +17,7 @@ use k8s_openapi::apimachinery::pkg::apis::meta::v1 as k8s_meta_v1;
 pub use kube_core::response::Status;
 use serde::de::DeserializeOwned;
 use serde_json::{self, Value};
+use std::sync::Arc;
 #[cfg(feature = "ws")]
 use tokio_tungstenite::{tungstenite as ws, WebSocketStream};
 use tokio_util::{
@@ -27,7 +28,7 @@ use tower::{buffer::Buffer, util::BoxService, BoxError, Layer, Service, ServiceE
 use tower_http::map_response_body::MapResponseBodyLayer;
 
 pub use self::body::Body;
-use crate::{api::WatchEvent, error::ErrorResponse, Config, Error, Result};
+use crate::{api::WatchEvent, discovery::ScopeCache, error::ErrorResponse, Config, Error, Result};
 
 mod auth;
 mod body;
@@ -65,7 +66,10 @@ pub use auth::oidc_errors;
 pub use upgrade::UpgradeConnectionError;
 
 #[cfg(feature = "ws")]
-pub use upgrade::WsStream;
+pub use upgrade::{
+    sec_websocket_key, sec_websocket_key_with, split_channel_frame, PeerInfo, SubProto, WsSessionLimiter,
+    WsSessionPermit, WsStream,
+};
 
 #[cfg(feature = "kubelet-debug")]
 #[cfg_attr(docsrs, doc(cfg(feature = "kubelet-debug")))]
@@ -86,6 +90,9 @@ pub struct Client {
     // - `BoxFuture` for dynamic response future type
     inner: Buffer<Request<Body>, BoxFuture<'static, Result<Response<Body>, BoxError>>>,
     default_ns: String,
+    scope_cache: Option<Arc<ScopeCache>>,
+    #[cfg(feature = "ws")]
+    ws_limiter: Option<upgrade::WsSessionLimiter>,
 }
 
 /// Constructors and low-level api interfaces.
@@ -139,9 +146,39 @@ impl Client {
         Self {
             inner: Buffer::new(BoxService::new(service), 1024),
             default_ns: default_namespace.into(),
+            scope_cache: None,
+            #[cfg(feature = "ws")]
+            ws_limiter: None,
         }
     }
 
+    /// Use known resource scopes to catch misuse of cluster scoped resources
+    ///
+    /// With a cache, [`Api::namespaced_with`](crate::Api::namespaced_with) logs a warning
+    /// when the cache knows the resource to be cluster scoped. Resources missing from the cache are not checked.
+    #[must_use]
+    pub fn with_scope_cache(mut self, cache: ScopeCache) -> Self {
+        self.scope_cache = Some(Arc::new(cache));
+        self
+    }
+
+    pub(crate) fn scope_cache(&self) -> Option<&ScopeCache> {
+        self.scope_cache.as_deref()
+    }
+
+    /// Limit the number of concurrent WebSocket sessions opened by this client
+    ///
+    /// Each call to [`Client::connect`], and so each exec, attach or port-forward, takes a permit
+    /// from `limiter` for the lifetime of the session. Connecting without a free permit fails with
+    /// [`UpgradeConnectionError::SessionLimitReached`]. Clones of the client share the limiter.
+    #[cfg(feature = "ws")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ws")))]
+    #[must_use]
+    pub fn with_ws_session_limiter(mut self, limiter: upgrade::WsSessionLimiter) -> Self {
+        self.ws_limiter = Some(limiter);
+        self
+    }
+
     /// Create and initialize a [`Client`] using the inferred configuration.
     ///
     /// Will use [`Config::infer`] which attempts to load the local kubeconfig first,
@@ -201,9 +238,53 @@ impl Client {
     pub async fn connect(
         &self,
         request: Request<Vec<u8>>,
+    ) -> Result<upgrade::WsStream<TokioIo<hyper::upgrade::Upgraded>>> {
+        self.connect_with_protocols(request, upgrade::WS_PROTOCOLS).await
+    }
+
+    /// Make WebSocket connection, offering the given subprotocols in order of preference.
+    ///
+    /// The server must pick one of the `offered` subprotocols, otherwise the handshake fails with
+    /// [`UpgradeConnectionError::SecWebSocketProtocolMismatch`]. [`Client::connect`] offers v5, then v4.
+    #[cfg(feature = "ws")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ws")))]
+    pub async fn connect_with_protocols(
+        &self,
+        request: Request<Vec<u8>>,
+        offered: &[upgrade::SubProto],
+    ) -> Result<upgrade::WsStream<TokioIo<hyper::upgrade::Upgraded>>> {
+        self.upgrade(request, offered, |_| {}).await
+    }
+
+    /// Make WebSocket connection, letting `prepare` modify the upgrade request before it is sent.
+    ///
+    /// The mandatory WebSocket handshake headers are already set when `prepare` is called,
+    /// so e.g. a request signature added there covers them.
+    /// Changing the `Sec-WebSocket-Key` header will make the handshake verification fail.
+    #[cfg(feature = "ws")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ws")))]
+    pub async fn connect_with(
+        &self,
+        request: Request<Vec<u8>>,
+        prepare: impl FnOnce(&mut Request<Vec<u8>>),
+    ) -> Result<upgrade::WsStream<TokioIo<hyper::upgrade::Upgraded>>> {
+        self.upgrade(request, upgrade::WS_PROTOCOLS, prepare).await
+    }
+
+    #[cfg(feature = "ws")]
+    async fn upgrade(
+        &self,
+        request: Request<Vec<u8>>,
+        offered: &[upgrade::SubProto],
+        prepare: impl FnOnce(&mut Request<Vec<u8>>),
     ) -> Result<upgrade::WsStream<TokioIo<hyper::upgrade::Upgraded>>> {
         use http::header::HeaderValue;
-        use upgrade::WS_PROTOCOLS;
+        let permit = self
+            .ws_limiter
+            .as_ref()
+            .map(upgrade::WsSessionLimiter::try_acquire)
+            .transpose()
+            .map_err(Error::UpgradeConnection)?;
         let (mut parts, body) = request.into_parts();
         parts
             .headers
@@ -220,24 +301,42 @@ impl Client {
             http::header::SEC_WEBSOCKET_KEY,
             key.parse().expect("valid header value"),
         );
-        // Attempt to use the binary subprotocol v5, to get JSON `Status` object in `error` channel (3).
+        // By default, attempt to use the binary subprotocol v5, to get JSON `Status` object in `error` channel (3).
         // introduced in v4, and to support stream close (v5). There's no official documentation
         // about this protocol, but it's described in
         // [kubernetes/kubernetes/blob/master/staging/src/k8s.io/apimachinery/pkg/util/remotecommand/constants.go]
         parts.headers.insert(
             http::header::SEC_WEBSOCKET_PROTOCOL,
-            HeaderValue::from_static(WS_PROTOCOLS),
+            upgrade::protocols_header(offered)
+                .parse()
+                .expect("valid header value"),
         );
 
-        let res = self.send(Request::from_parts(parts, Body::from(body))).await?;
-        let proto = upgrade::verify_response(&res, &key).map_err(Error::UpgradeConnection)?;
+        let mut request = Request::from_parts(parts, body);
+        prepare(&mut request);
+
+        let res = self.send(request.map(Body::from)).await?;
+        if res.status() != http::StatusCode::SWITCHING_PROTOCOLS {
+            return Err(Error::UpgradeConnection(
+                upgrade::protocol_switch_error(res).await,
+            ));
+        }
+        let proto = upgrade::verify_response(&res, &key, offered).map_err(Error::UpgradeConnection)?;
         tracing::info!("WebSocket connection established with protocol: {:?}", proto);
+        let peer = upgrade::peer_info(&res);
         match hyper::upgrade::on(res).await {
-            Ok(upgraded) => Ok(upgrade::WsStream::new(
-                WebSocketStream::from_raw_socket(TokioIo::new(upgraded), ws::protocol::Role::Client, None)
-                    .await,
-                proto,
-            )),
+            Ok(upgraded) => {
+                let stream = upgrade::WsStream::new(
+                    WebSocketStream::from_raw_socket(TokioIo::new(upgraded), ws::protocol::Role::Client, None)
+                        .await,
+                    proto,
+                )
+                .with_peer_info(peer);
+                Ok(match permit {
+                    Some(permit) => stream.with_permit(permit),
+                    None => stream,
+                })
+            }
 
             Err(e) => Err(Error::UpgradeConnection(
                 UpgradeConnectionError::GetPendingUpgrade(e),
@@ -251,22 +350,44 @@ impl Client {
     where
         T: DeserializeOwned,
     {
-        let text = self.request_text(request).await?;
-
-        serde_json::from_str(&text).map_err(|e| {
-            tracing::warn!("{}, {:?}", text, e);
-            Error::SerdeError(e)
-        })
+        let (value, _warnings) = self.request_with_warnings(request).await?;
+        Ok(value)
     }
 
     /// Perform a raw HTTP request against the API and get back the response
     /// as a string
     pub async fn request_text(&self, request: Request<Vec<u8>>) -> Result<String> {
+        let (text, _warnings) = self.request_text_with_warnings(request).await?;
+        Ok(text)
+    }
+
+    /// Like [`Client::request_text`], also returning the `Warning` headers of the response
+    async fn request_text_with_warnings(&self, request: Request<Vec<u8>>) -> Result<(String, Vec<String>)> {
         let res = self.send(request.map(Body::from)).await?;
         let res = handle_api_errors(res).await?;
+        let warnings = res
+            .headers()
+            .get_all(http::header::WARNING)
+            .iter()
+            .filter_map(|h| h.to_str().ok())
+            .map(String::from)
+            .collect();
         let body_bytes = res.into_body().collect().await?.to_bytes();
         let text = String::from_utf8(body_bytes.to_vec()).map_err(Error::FromUtf8)?;
-        Ok(text)
+        Ok((text, warnings))
+    }
+
+    /// Like [`Client::request`], also returning the `Warning` headers of the response
+    async fn request_with_warnings<T>(&self, request: Request<Vec<u8>>) -> Result<(T, Vec<String>)>
+    where
+        T: DeserializeOwned,
+    {
+        let (text, warnings) = self.request_text_with_warnings(request).await?;
+        let value = serde_json::from_str(&text).map_err(|e| {
+            tracing::warn!("{}, {:?}", text, e);
+            Error::SerdeError(e)
+        })?;
+        Ok((value, warnings))
     }
 
     /// Perform a raw HTTP request against the API and stream the response body.
@@ -420,8 +541,20 @@ impl Client {
     /// # }
     /// ```
     pub async fn list_api_group_resources(&self, apiversion: &str) -> Result<k8s_meta_v1::APIResourceList> {
+        let (list, _warnings) = self.list_api_group_resources_with_warnings(apiversion).await?;
+        Ok(list)
+    }
+
+    /// Lists resources served in given API group, along with any `Warning` headers in the response.
+    ///
+    /// The apiserver uses [`Warning` headers](https://kubernetes.io/blog/2020/09/03/warnings/)
+    /// to flag deprecated apis.
+    pub async fn list_api_group_resources_with_warnings(
+        &self,
+        apiversion: &str,
+    ) -> Result<(k8s_meta_v1::APIResourceList, Vec<String>)> {
         let url = format!("/apis/{apiversion}");
-        self.request(
+        self.request_with_warnings(
             Request::builder()
                 .uri(url)
                 .body(vec![])
@@ -443,8 +576,19 @@ impl Client {
 
     /// Lists resources served in particular `core` group version.
     pub async fn list_core_api_resources(&self, version: &str) -> Result<k8s_meta_v1::APIResourceList> {
+        let (list, _warnings) = self.list_core_api_resources_with_warnings(version).await?;
+        Ok(list)
+    }
+
+    /// Lists resources served in particular `core` group version, along with any `Warning` headers in the response.
+    ///
+    /// See [`Client::list_api_group_resources_with_warnings`].
+    pub async fn list_core_api_resources_with_warnings(
+        &self,
+        version: &str,
+    ) -> Result<(k8s_meta_v1::APIResourceList, Vec<String>)> {
         let url = format!("/api/{version}");
-        self.request(
+        self.request_with_warnings(
             Request::builder()
                 .uri(url)
                 .body(vec![])
@@ -516,6 +660,181 @@ mod tests {
         assert_eq!(client.default_namespace(), "test-namespace");
     }
 
+    #[cfg(feature = "ws")]
+    #[tokio::test]
+    async fn connect_with_can_sign_upgrade_request() {
+        let (mock_service, handle) = mock::pair::<Request<Body>, Response<Body>>();
+        let spawned = tokio::spawn(async move {
+            let mut handle = pin!(handle);
+            let (request, send) = handle.next_request().await.expect("service not called");
+            let headers = request.headers();
+            assert!(headers.contains_key(http::header::SEC_WEBSOCKET_KEY));
+            assert_eq!(headers.get("x-signature").unwrap(), "signed:websocket");
+            send.send_response(
+                Response::builder()
+                    .status(http::StatusCode::FORBIDDEN)
+                    .body(Body::empty())
+                    .unwrap(),
+            );
+        });
+
+        let client = Client::new(mock_service, "default");
+        let req = Request::get("/api/v1/namespaces/default/pods/test/exec").body(vec![]).unwrap();
+        let res = client
+            .connect_with(req, |req| {
+                // sign over a header that must already be present
+                let upgrade = req.headers()[http::header::UPGRADE].to_str().unwrap().to_string();
+                let signature = format!("signed:{upgrade}").parse().unwrap();
+                req.headers_mut().insert("x-signature", signature);
+            })
+            .await;
+        assert!(matches!(res, Err(crate::Error::UpgradeConnection(_))));
+        spawned.await.unwrap();
+    }
+
+    #[cfg(feature = "ws")]
+    #[tokio::test]
+    async fn connect_with_protocols_offers_and_verifies_given_list() {
+        use super::upgrade::{SubProto, UpgradeConnectionError};
+        let (mock_service, handle) = mock::pair::<Request<Body>, Response<Body>>();
+        let spawned = tokio::spawn(async move {
+            let mut handle = pin!(handle);
+            let (request, send) = handle.next_request().await.expect("service not called");
+            let headers = request.headers();
+            assert_eq!(headers[http::header::SEC_WEBSOCKET_PROTOCOL], "v4.channel.k8s.io");
+            let key = headers[http::header::SEC_WEBSOCKET_KEY].as_bytes();
+            let accept = tokio_tungstenite::tungstenite::handshake::derive_accept_key(key);
+            // answer with a protocol that was not offered
+            send.send_response(
+                Response::builder()
+                    .status(http::StatusCode::SWITCHING_PROTOCOLS)
+                    .header(http::header::UPGRADE, "websocket")
+                    .header(http::header::CONNECTION, "Upgrade")
+                    .header(http::header::SEC_WEBSOCKET_ACCEPT, accept)
+                    .header(http::header::SEC_WEBSOCKET_PROTOCOL, "v5.channel.k8s.io")
+                    .body(Body::empty())
+                    .unwrap(),
+            );
+        });
+
+        let client = Client::new(mock_service, "default");
+        let req = Request::get("/api/v1/namespaces/default/pods/test/exec").body(vec![]).unwrap();
+        let res = client.connect_with_protocols(req, &[SubProto::V4]).await;
+        assert!(matches!(
+            res,
+            Err(crate::Error::UpgradeConnection(
+                UpgradeConnectionError::SecWebSocketProtocolMismatch
+            ))
+        ));
+        spawned.await.unwrap();
+    }
+
+    #[cfg(feature = "ws")]
+    #[tokio::test]
+    async fn connect_records_peer_address() {
+        use crate::{client::ConfigExt, Config};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut sock, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0; 4096];
+            let mut len = 0;
+            while !buf[..len].ends_with(b"\r\n\r\n") {
+                len += sock.read(&mut buf[len..]).await.unwrap();
+            }
+            let head = String::from_utf8_lossy(&buf[..len]).into_owned();
+            let key = head
+                .lines()
+                .filter_map(|l| l.split_once(':'))
+                .find(|(name, _)| name.eq_ignore_ascii_case("sec-websocket-key"))
+                .map(|(_, value)| value.trim())
+                .unwrap();
+            let accept = tokio_tungstenite::tungstenite::handshake::derive_accept_key(key.as_bytes());
+            let res = format!(
+                "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+                 Sec-WebSocket-Accept: {accept}\r\nSec-WebSocket-Protocol: v5.channel.k8s.io\r\n\r\n"
+            );
+            sock.write_all(res.as_bytes()).await.unwrap();
+            sock
+        });
+
+        let config = Config::new(format!("http://{addr}").parse().unwrap());
+        let connector = hyper_util::client::legacy::Client::builder(hyper_util::rt::TokioExecutor::new())
+            .build_http::<Body>();
+        let service = tower::ServiceBuilder::new()
+            .layer(config.base_uri_layer())
+            .service(connector);
+        let client = Client::new(service, "default");
+        let req = Request::get("/api/v1/namespaces/default/pods/test/exec").body(vec![]).unwrap();
+        let stream = client.connect(req).await.unwrap();
+        let _sock = server.await.unwrap();
+        let peer = stream.peer_info().expect("peer info from HttpConnector");
+        assert_eq!(peer.remote_addr, addr);
+        assert_eq!(peer.local_addr.ip(), addr.ip());
+    }
+
+    #[cfg(feature = "ws")]
+    #[tokio::test]
+    async fn connect_rejects_sessions_beyond_limit() {
+        use super::{upgrade::UpgradeConnectionError, WsSessionLimiter};
+        let (mock_service, handle) = mock::pair::<Request<Body>, Response<Body>>();
+        let spawned = tokio::spawn(async move {
+            let mut handle = pin!(handle);
+            let (_request, send) = handle.next_request().await.expect("service not called");
+            send.send_response(
+                Response::builder()
+                    .status(http::StatusCode::FORBIDDEN)
+                    .body(Body::empty())
+                    .unwrap(),
+            );
+        });
+
+        let limiter = WsSessionLimiter::new(1);
+        let client = Client::new(mock_service, "default").with_ws_session_limiter(limiter.clone());
+        let exec = || Request::get("/api/v1/namespaces/default/pods/test/exec").body(vec![]).unwrap();
+        // a failed upgrade releases its permit
+        assert!(client.connect(exec()).await.is_err());
+        spawned.await.unwrap();
+        assert_eq!(limiter.active(), 0);
+
+        let _held = limiter.try_acquire().unwrap();
+        let res = client.connect(exec()).await;
+        assert!(matches!(
+            res,
+            Err(crate::Error::UpgradeConnection(
+                UpgradeConnectionError::SessionLimitReached(1)
+            ))
+        ));
+    }
+
+    #[cfg(feature = "ws")]
+    #[tokio::test]
+    async fn trace_context_reaches_exec_upgrade() {
+        use crate::api::{AttachParams, TraceContext};
+        let traceparent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let (mock_service, handle) = mock::pair::<Request<Body>, Response<Body>>();
+        let spawned = tokio::spawn(async move {
+            let mut handle = pin!(handle);
+            let (request, send) = handle.next_request().await.expect("service not called");
+            assert!(request.uri().path().ends_with("/pods/web/exec"));
+            assert_eq!(request.headers().get("traceparent").unwrap(), traceparent);
+            assert!(request.headers().contains_key(http::header::SEC_WEBSOCKET_KEY));
+            send.send_response(
+                Response::builder()
+                    .status(http::StatusCode::FORBIDDEN)
+                    .body(Body::empty())
+                    .unwrap(),
+            );
+        });
+
+        let pods: Api<Pod> = Api::default_namespaced(Client::new(mock_service, "default"))
+            .with_trace_context(TraceContext::new(traceparent).unwrap());
+        assert!(pods.exec("web", ["ls"], &AttachParams::default()).await.is_err());
+        spawned.await.unwrap();
+    }
+
     #[tokio::test]
     async fn test_mock() {
         let (mock_service, handle) = mock::pair::<Request<Body>, Response<Body>>();
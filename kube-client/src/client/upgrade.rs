@@ -1,7 +1,10 @@
-use std::str::FromStr;
+use std::{net::SocketAddr, str::FromStr, sync::Arc};
 
 use http::{self, Response, StatusCode};
+use http_body_util::BodyExt;
+use hyper_util::client::legacy::connect::HttpInfo;
 use thiserror::Error;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use tokio_tungstenite::{tungstenite as ws, WebSocketStream};
 
 use crate::client::Body;
@@ -12,15 +15,49 @@ pub const WS_PROTOCOL_V4: &str = "v4.channel.k8s.io";
 // Binary subprotocol v5. implements v4 and adds CLOSE signal.
 pub const WS_PROTOCOL_V5: &str = "v5.channel.k8s.io";
 
-pub const WS_PROTOCOLS: &str = "v5.channel.k8s.io,v4.channel.k8s.io";
+// Subprotocols offered when connecting, in order of preference.
+pub const WS_PROTOCOLS: &[SubProto] = &[SubProto::V5, SubProto::V4];
 
+/// Channel subprotocols of the Kubernetes streaming apis (exec, attach and port-forward)
+///
+/// Ordered by capability, newer protocols compare greater.
 #[cfg(feature = "ws")]
-#[derive(Debug, Clone, Copy)]
+#[cfg_attr(docsrs, doc(cfg(feature = "ws")))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum SubProto {
+    /// `v4.channel.k8s.io`, which sends a JSON `Status` on the error channel
     V4,
+    /// `v5.channel.k8s.io`, which adds the CLOSE signal to v4
     V5,
 }
 
+impl SubProto {
+    /// The `Sec-WebSocket-Protocol` name of this subprotocol
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SubProto::V4 => WS_PROTOCOL_V4,
+            SubProto::V5 => WS_PROTOCOL_V5,
+        }
+    }
+
+    /// The most capable of the given subprotocols
+    ///
+    /// ```
+    /// use kube_client::client::SubProto;
+    /// assert!(SubProto::V4 < SubProto::V5);
+    /// assert_eq!(SubProto::best_from(&[SubProto::V4, SubProto::V5]), Some(SubProto::V5));
+    /// assert_eq!(SubProto::best_from(&[]), None);
+    /// ```
+    pub fn best_from(protos: &[SubProto]) -> Option<SubProto> {
+        protos.iter().max().copied()
+    }
+
+    /// Whether this subprotocol supports the CLOSE signal, i.e. only v5
+    pub fn supports_close(&self) -> bool {
+        matches!(self, SubProto::V5)
+    }
+}
+
 impl FromStr for SubProto {
     type Err = &'static str;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -37,6 +74,8 @@ impl FromStr for SubProto {
 pub struct WsStream<S> {
     stream: WebSocketStream<S>,
     proto: SubProto,
+    permit: Option<WsSessionPermit>,
+    peer: Option<PeerInfo>,
 }
 
 impl<S> WsStream<S>
@@ -45,18 +84,157 @@ where
 {
     #[allow(missing_docs)]
     pub fn new(stream: WebSocketStream<S>, proto: SubProto) -> Self {
-        Self { stream, proto }
+        Self {
+            stream,
+            proto,
+            permit: None,
+            peer: None,
+        }
     }
-    #[allow(missing_docs)]
+
+    pub(crate) fn with_peer_info(mut self, peer: Option<PeerInfo>) -> Self {
+        self.peer = peer;
+        self
+    }
+
+    /// Details about the remote peer of the underlying connection, for e.g. audit logging
+    ///
+    /// Known for sessions from [`Client::connect`](crate::Client::connect) over connections that expose their addresses,
+    /// and `None` for streams made with [`WsStream::new`].
+    pub fn peer_info(&self) -> Option<PeerInfo> {
+        self.peer
+    }
+
+    /// Hold a [`WsSessionPermit`] for as long as this session, or a process or port-forward made from it, lives
+    #[must_use]
+    pub fn with_permit(mut self, permit: WsSessionPermit) -> Self {
+        self.permit = Some(permit);
+        self
+    }
+
+    /// Unwrap the underlying WebSocket stream
+    ///
+    /// Any [`WsSessionPermit`] held by this session is released.
     pub fn into_inner(self) -> WebSocketStream<S> {
         self.stream
     }
+
+    pub(crate) fn into_parts(self) -> (WebSocketStream<S>, Option<WsSessionPermit>) {
+        (self.stream, self.permit)
+    }
+    /// The subprotocol negotiated for this session
+    pub fn protocol(&self) -> SubProto {
+        self.proto
+    }
+
     #[allow(missing_docs)]
     pub fn supports_closing(&self) -> bool {
-        matches!(self.proto, SubProto::V5)
+        self.proto.supports_close()
+    }
+
+    /// Demultiplex this stream as a port-forward connection for the given ports.
+    ///
+    /// Each port uses a pair of channels (data and error), and the initial frame on each
+    /// channel carries the port number, which is verified against `ports`.
+    pub fn portforward(self, ports: &[u16]) -> crate::api::Portforwarder {
+        crate::api::Portforwarder::new(self.stream, ports, self.permit)
+    }
+}
+
+/// Connection details about the remote end of a [`WsStream`]
+#[cfg(feature = "ws")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ws")))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeerInfo {
+    /// Address of the remote end of the connection
+    pub remote_addr: SocketAddr,
+    /// Address of the local end of the connection
+    pub local_addr: SocketAddr,
+}
+
+// Read the connection addresses hyper's `HttpConnector` attaches to responses
+pub fn peer_info<B>(res: &Response<B>) -> Option<PeerInfo> {
+    res.extensions().get::<HttpInfo>().map(|info| PeerInfo {
+        remote_addr: info.remote_addr(),
+        local_addr: info.local_addr(),
+    })
+}
+
+/// Bounds the number of concurrent WebSocket sessions, such as exec, attach and port-forward
+///
+/// Each session holds a [`WsSessionPermit`] until it is dropped. Clones share the same limit,
+/// so a single limiter can be given to a [`Client`](crate::Client) with
+/// [`Client::with_ws_session_limiter`](crate::Client::with_ws_session_limiter),
+/// which then rejects upgrades beyond the limit with [`UpgradeConnectionError::SessionLimitReached`].
+/// To queue instead, [`acquire`](Self::acquire) a permit and attach it with [`WsStream::with_permit`].
+#[cfg(feature = "ws")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ws")))]
+#[derive(Debug, Clone)]
+pub struct WsSessionLimiter {
+    semaphore: Arc<Semaphore>,
+    max_concurrent: usize,
+}
+
+/// A slot of a [`WsSessionLimiter`], released when dropped
+#[cfg(feature = "ws")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ws")))]
+#[derive(Debug)]
+pub struct WsSessionPermit {
+    _permit: OwnedSemaphorePermit,
+}
+
+impl WsSessionLimiter {
+    /// Allow at most `max_concurrent` sessions at a time
+    pub fn new(max_concurrent: usize) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(max_concurrent)),
+            max_concurrent,
+        }
+    }
+
+    /// The maximum number of concurrent sessions
+    pub fn max_concurrent(&self) -> usize {
+        self.max_concurrent
+    }
+
+    /// The number of sessions currently holding a permit
+    pub fn active(&self) -> usize {
+        self.max_concurrent - self.semaphore.available_permits()
+    }
+
+    /// Take a permit if one is free, failing with [`UpgradeConnectionError::SessionLimitReached`] otherwise
+    pub fn try_acquire(&self) -> Result<WsSessionPermit, UpgradeConnectionError> {
+        let permit = self
+            .semaphore
+            .clone()
+            .try_acquire_owned()
+            .map_err(|_| UpgradeConnectionError::SessionLimitReached(self.max_concurrent))?;
+        Ok(WsSessionPermit { _permit: permit })
+    }
+
+    /// Wait until a permit is free and take it
+    pub async fn acquire(&self) -> WsSessionPermit {
+        let permit = self
+            .semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("session semaphore is never closed");
+        WsSessionPermit { _permit: permit }
     }
 }
 
+/// Split a binary frame of a channel subprotocol into its channel number and payload
+///
+/// Returns `None` for an empty frame. A frame of a single byte carries only the channel number,
+/// as is sent when a channel is opened, and yields an empty payload.
+#[cfg(feature = "ws")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ws")))]
+pub fn split_channel_frame(data: &[u8]) -> Option<(u8, &[u8])> {
+    let (channel, payload) = data.split_first()?;
+    Some((*channel, payload))
+}
+
 /// Possible errors from upgrading to a WebSocket connection
 #[cfg(feature = "ws")]
 #[cfg_attr(docsrs, doc(cfg(feature = "ws")))]
@@ -65,9 +243,12 @@ pub enum UpgradeConnectionError {
     /// The server did not respond with [`SWITCHING_PROTOCOLS`] status when upgrading the
     /// connection.
     ///
+    /// Carries the server's explanation for the failure when one was returned in the response body;
+    /// either the `message` of a `Status` object, or the raw (truncated) body.
+    ///
     /// [`SWITCHING_PROTOCOLS`]: http::status::StatusCode::SWITCHING_PROTOCOLS
-    #[error("failed to switch protocol: {0}")]
-    ProtocolSwitch(http::status::StatusCode),
+    #[error("failed to switch protocol: {0}{}", display_reason(.1))]
+    ProtocolSwitch(http::status::StatusCode, String),
 
     /// `Upgrade` header was not set to `websocket` (case insensitive)
     #[error("upgrade header was not set to websocket")]
@@ -88,13 +269,68 @@ pub enum UpgradeConnectionError {
     /// Failed to get pending HTTP upgrade.
     #[error("failed to get pending HTTP upgrade: {0}")]
     GetPendingUpgrade(#[source] hyper::Error),
+
+    /// `Sec-WebSocket-Extensions` contained an extension that was not offered.
+    ///
+    /// No extensions are offered, and an imposed one such as `permessage-deflate` would corrupt the framing.
+    #[error("unexpected Sec-WebSocket-Extensions: {0}")]
+    UnexpectedExtension(String),
+
+    /// The [`WsSessionLimiter`] of the client had no free permit among its maximum number of sessions.
+    #[error("too many concurrent WebSocket sessions, limit is {0}")]
+    SessionLimitReached(usize),
+}
+
+impl UpgradeConnectionError {
+    /// Whether retrying the connection could succeed
+    ///
+    /// Servers refusing the upgrade with a 5xx or `429 Too Many Requests` status,
+    /// transient failures of the pending upgrade (closed, canceled or timed out connections),
+    /// and a reached session limit are retryable.
+    /// Handshake mismatches and any other refusals are fatal.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::ProtocolSwitch(status, _) => {
+                status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS
+            }
+            Self::GetPendingUpgrade(err) => {
+                err.is_closed() || err.is_canceled() || err.is_incomplete_message() || err.is_timeout()
+            }
+            Self::SessionLimitReached(_) => true,
+            Self::MissingUpgradeWebSocketHeader
+            | Self::MissingConnectionUpgradeHeader
+            | Self::SecWebSocketAcceptKeyMismatch
+            | Self::SecWebSocketProtocolMismatch
+            | Self::UnexpectedExtension(_) => false,
+        }
+    }
+}
+
+fn display_reason(reason: &str) -> String {
+    if reason.is_empty() {
+        String::new()
+    } else {
+        format!(": {reason}")
+    }
+}
+
+// Upper bound on how much of a failed upgrade response body we read for the error.
+const MAX_ERROR_BODY_SIZE: usize = 4096;
+
+// Build the `Sec-WebSocket-Protocol` request header value offering the given subprotocols.
+pub fn protocols_header(offered: &[SubProto]) -> String {
+    offered.iter().map(SubProto::as_str).collect::<Vec<_>>().join(",")
 }
 
 // Verify upgrade response according to RFC6455.
-// Based on `tungstenite` and added subprotocol verification.
-pub fn verify_response(res: &Response<Body>, key: &str) -> Result<SubProto, UpgradeConnectionError> {
+// Based on `tungstenite` and added subprotocol verification against the `offered` subprotocols.
+pub fn verify_response(
+    res: &Response<Body>,
+    key: &str,
+    offered: &[SubProto],
+) -> Result<SubProto, UpgradeConnectionError> {
     if res.status() != StatusCode::SWITCHING_PROTOCOLS {
-        return Err(UpgradeConnectionError::ProtocolSwitch(res.status()));
+        return Err(UpgradeConnectionError::ProtocolSwitch(res.status(), String::new()));
     }
 
     let headers = res.headers();
@@ -124,21 +360,280 @@ pub fn verify_response(res: &Response<Body>, key: &str) -> Result<SubProto, Upgr
     {
         return Err(UpgradeConnectionError::SecWebSocketAcceptKeyMismatch);
     }
-    // Check for supported subprotocol and return it
+    // No extensions are offered, so the server must not use any
+    if let Some(extensions) = headers
+        .get(http::header::SEC_WEBSOCKET_EXTENSIONS)
+        .filter(|h| !h.as_bytes().iter().all(u8::is_ascii_whitespace))
+    {
+        let extensions = String::from_utf8_lossy(extensions.as_bytes()).into_owned();
+        return Err(UpgradeConnectionError::UnexpectedExtension(extensions));
+    }
+
+    // Check for an offered subprotocol and return it
     headers
         .get(http::header::SEC_WEBSOCKET_PROTOCOL)
-        .map(|h| {
-            SubProto::from_str(h.to_str().unwrap_or(""))
-                .map_err(|_| UpgradeConnectionError::SecWebSocketProtocolMismatch)
-        })
-        .take()
-        .unwrap_or(Err(UpgradeConnectionError::SecWebSocketProtocolMismatch))
+        .and_then(|h| SubProto::from_str(h.to_str().unwrap_or("")).ok())
+        .filter(|proto| offered.contains(proto))
+        .ok_or(UpgradeConnectionError::SecWebSocketProtocolMismatch)
+}
+
+// Build a `ProtocolSwitch` error from a response that failed to upgrade.
+// Reads up to `MAX_ERROR_BODY_SIZE` bytes of the body, preferring the `message` of a `Status` object.
+pub async fn protocol_switch_error(res: Response<Body>) -> UpgradeConnectionError {
+    let status = res.status();
+    let mut body = res.into_body();
+    let mut buf = Vec::new();
+    while let Some(Ok(frame)) = body.frame().await {
+        if let Ok(data) = frame.into_data() {
+            buf.extend_from_slice(&data);
+            if buf.len() >= MAX_ERROR_BODY_SIZE {
+                buf.truncate(MAX_ERROR_BODY_SIZE);
+                break;
+            }
+        }
+    }
+    let reason = match serde_json::from_slice::<kube_core::Status>(&buf) {
+        Ok(s) if !s.message.is_empty() => s.message,
+        _ => String::from_utf8_lossy(&buf).trim().to_string(),
+    };
+    UpgradeConnectionError::ProtocolSwitch(status, reason)
 }
 
 /// Generate a random key for the `Sec-WebSocket-Key` header.
 /// This must be nonce consisting of a randomly selected 16-byte value in base64.
 pub fn sec_websocket_key() -> String {
-    use base64::Engine;
+    sec_websocket_key_with(&base64::engine::general_purpose::STANDARD)
+}
+
+/// Generate a random key for the `Sec-WebSocket-Key` header using the given base64 `engine`.
+///
+/// RFC6455 requires standard padded base64, which is what [`sec_websocket_key`] uses.
+/// Other engines are only useful for test harnesses or compatibility shims.
+///
+/// ```
+/// use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+/// use kube_client::client::sec_websocket_key_with;
+/// let key = sec_websocket_key_with(&URL_SAFE_NO_PAD);
+/// assert_eq!(key.len(), 22);
+/// ```
+pub fn sec_websocket_key_with(engine: &impl base64::Engine) -> String {
     let r: [u8; 16] = rand::random();
-    base64::engine::general_purpose::STANDARD.encode(r)
+    engine.encode(r)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::SinkExt;
+    use tokio::io::AsyncReadExt;
+
+    #[tokio::test]
+    async fn session_limiter_bounds_concurrent_sessions() {
+        let limiter = WsSessionLimiter::new(2);
+        let first = limiter.try_acquire().unwrap();
+        let _second = limiter.acquire().await;
+        assert_eq!(limiter.active(), 2);
+        assert!(matches!(
+            limiter.clone().try_acquire(),
+            Err(UpgradeConnectionError::SessionLimitReached(2))
+        ));
+        assert!(UpgradeConnectionError::SessionLimitReached(2).is_retryable());
+
+        let queued = tokio::spawn({
+            let limiter = limiter.clone();
+            async move { limiter.acquire().await }
+        });
+        drop(first);
+        let _third = queued.await.unwrap();
+        assert_eq!(limiter.active(), 2);
+        assert_eq!(limiter.max_concurrent(), 2);
+    }
+
+    #[test]
+    fn sec_websocket_key_engines() {
+        use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+        use base64::Engine;
+
+        let key = sec_websocket_key();
+        assert_eq!(key.len(), 24);
+        assert!(key.ends_with("=="));
+        assert_eq!(STANDARD.decode(&key).unwrap().len(), 16);
+
+        let key = sec_websocket_key_with(&URL_SAFE_NO_PAD);
+        assert_eq!(key.len(), 22);
+        assert!(!key.contains(['=', '+', '/']));
+        assert_eq!(URL_SAFE_NO_PAD.decode(&key).unwrap().len(), 16);
+    }
+
+    fn upgrade_response(key: &str, proto: &str) -> Response<Body> {
+        Response::builder()
+            .status(StatusCode::SWITCHING_PROTOCOLS)
+            .header(http::header::UPGRADE, "websocket")
+            .header(http::header::CONNECTION, "Upgrade")
+            .header(
+                http::header::SEC_WEBSOCKET_ACCEPT,
+                ws::handshake::derive_accept_key(key.as_bytes()),
+            )
+            .header(http::header::SEC_WEBSOCKET_PROTOCOL, proto)
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[test]
+    fn verify_response_only_accepts_offered_protocols() {
+        assert_eq!(
+            protocols_header(WS_PROTOCOLS),
+            "v5.channel.k8s.io,v4.channel.k8s.io"
+        );
+        let key = sec_websocket_key();
+        let res = upgrade_response(&key, WS_PROTOCOL_V4);
+        assert!(matches!(
+            verify_response(&res, &key, &[SubProto::V5]),
+            Err(UpgradeConnectionError::SecWebSocketProtocolMismatch)
+        ));
+        assert_eq!(
+            verify_response(&res, &key, &[SubProto::V4, SubProto::V5]).unwrap(),
+            SubProto::V4
+        );
+    }
+
+    #[tokio::test]
+    async fn upgrade_errors_classified_by_retryability() {
+        use UpgradeConnectionError::*;
+        for status in [
+            StatusCode::SERVICE_UNAVAILABLE,
+            StatusCode::BAD_GATEWAY,
+            StatusCode::TOO_MANY_REQUESTS,
+        ] {
+            assert!(ProtocolSwitch(status, String::new()).is_retryable());
+        }
+        for status in [StatusCode::FORBIDDEN, StatusCode::BAD_REQUEST, StatusCode::OK] {
+            assert!(!ProtocolSwitch(status, String::new()).is_retryable());
+        }
+        assert!(!MissingUpgradeWebSocketHeader.is_retryable());
+        assert!(!MissingConnectionUpgradeHeader.is_retryable());
+        assert!(!SecWebSocketAcceptKeyMismatch.is_retryable());
+        assert!(!SecWebSocketProtocolMismatch.is_retryable());
+
+        // a response that was never upgraded is a usage error, not a transient one
+        let err = hyper::upgrade::on(Response::new(Body::empty())).await.unwrap_err();
+        assert!(!GetPendingUpgrade(err).is_retryable());
+    }
+
+    #[test]
+    fn subprotocols_ordered_by_capability() {
+        assert!(SubProto::V5 > SubProto::V4);
+        assert_eq!(SubProto::best_from(&[SubProto::V4, SubProto::V5]), Some(SubProto::V5));
+        assert_eq!(SubProto::best_from(&[SubProto::V4]), Some(SubProto::V4));
+        assert_eq!(SubProto::best_from(&[]), None);
+    }
+
+    #[test]
+    fn verify_response_rejects_unoffered_extensions() {
+        let key = sec_websocket_key();
+        let mut res = upgrade_response(&key, WS_PROTOCOL_V5);
+        assert_eq!(verify_response(&res, &key, WS_PROTOCOLS).unwrap(), SubProto::V5);
+
+        res.headers_mut().insert(
+            http::header::SEC_WEBSOCKET_EXTENSIONS,
+            http::HeaderValue::from_static("permessage-deflate; client_max_window_bits"),
+        );
+        match verify_response(&res, &key, WS_PROTOCOLS) {
+            Err(UpgradeConnectionError::UnexpectedExtension(ext)) => {
+                assert_eq!(ext, "permessage-deflate; client_max_window_bits")
+            }
+            other => panic!("expected UnexpectedExtension, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn split_channel_frames() {
+        assert_eq!(split_channel_frame(&[]), None);
+        assert_eq!(split_channel_frame(&[3]), Some((3, &[][..])));
+        assert_eq!(split_channel_frame(b"\x01hello"), Some((1, &b"hello"[..])));
+    }
+
+    #[test]
+    fn only_v5_supports_close() {
+        assert!(SubProto::V5.supports_close());
+        assert!(!SubProto::V4.supports_close());
+    }
+
+    #[tokio::test]
+    async fn stream_exposes_negotiated_protocol() {
+        let (io, _other) = tokio::io::duplex(64);
+        let ws = WebSocketStream::from_raw_socket(io, ws::protocol::Role::Client, None).await;
+        let stream = WsStream::new(ws, SubProto::V4);
+        assert_eq!(stream.protocol(), SubProto::V4);
+        assert_eq!(stream.supports_closing(), stream.protocol().supports_close());
+    }
+
+    #[tokio::test]
+    async fn peer_info_requires_connection_info() {
+        let res = Response::new(Body::empty());
+        assert_eq!(peer_info(&res), None);
+
+        let (io, _other) = tokio::io::duplex(64);
+        let ws = WebSocketStream::from_raw_socket(io, ws::protocol::Role::Client, None).await;
+        assert_eq!(WsStream::new(ws, SubProto::V5).peer_info(), None);
+    }
+
+    #[tokio::test]
+    async fn portforward_handles_preamble_and_data() {
+        let (client_io, server_io) = tokio::io::duplex(1024);
+        let client = WebSocketStream::from_raw_socket(client_io, ws::protocol::Role::Client, None).await;
+        let mut server = WebSocketStream::from_raw_socket(server_io, ws::protocol::Role::Server, None).await;
+        let mut pf = WsStream::new(client, SubProto::V4).portforward(&[8080]);
+
+        let [lo, hi] = 8080u16.to_le_bytes();
+        // data and error channel preambles carry the port number
+        server.send(ws::Message::binary(vec![0, lo, hi])).await.unwrap();
+        server.send(ws::Message::binary(vec![1, lo, hi])).await.unwrap();
+        server.send(ws::Message::binary(b"\x00hello".to_vec())).await.unwrap();
+
+        let mut port = pf.take_stream(8080).unwrap();
+        let mut buf = [0u8; 5];
+        port.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"hello");
+        assert!(pf.take_stream(8080).is_none());
+    }
+
+    #[tokio::test]
+    async fn protocol_switch_error_surfaces_status_message() {
+        let status = serde_json::json!({
+            "kind": "Status",
+            "apiVersion": "v1",
+            "status": "Failure",
+            "message": "container not found (\"foo\")",
+            "reason": "BadRequest",
+            "code": 400
+        });
+        let res = Response::builder()
+            .status(StatusCode::BAD_REQUEST)
+            .body(Body::from(serde_json::to_vec(&status).unwrap()))
+            .unwrap();
+        let err = protocol_switch_error(res).await;
+        assert!(matches!(
+            &err,
+            UpgradeConnectionError::ProtocolSwitch(code, reason)
+                if *code == StatusCode::BAD_REQUEST && reason == "container not found (\"foo\")"
+        ));
+        assert_eq!(
+            err.to_string(),
+            "failed to switch protocol: 400 Bad Request: container not found (\"foo\")"
+        );
+    }
+
+    #[tokio::test]
+    async fn protocol_switch_error_truncates_raw_body() {
+        let res = Response::builder()
+            .status(StatusCode::FORBIDDEN)
+            .body(Body::from(vec![b'x'; MAX_ERROR_BODY_SIZE * 2]))
+            .unwrap();
+        let UpgradeConnectionError::ProtocolSwitch(status, reason) = protocol_switch_error(res).await else {
+            panic!("expected ProtocolSwitch error");
+        };
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(reason.len(), MAX_ERROR_BODY_SIZE);
+    }
 }
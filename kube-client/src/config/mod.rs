@@ -130,6 +130,9 @@ pub enum LoadDataError {
 #[derive(Debug, Clone)]
 pub struct Config {
     /// The configured cluster url
+    ///
+    /// Any path on this url prefixes the path of every request, so an apiserver behind a
+    /// path-rewriting proxy is reached with e.g. `https://example.com/k8s-proxy`.
     pub cluster_url: http::Uri,
     /// The configured default namespace
     pub default_namespace: String,
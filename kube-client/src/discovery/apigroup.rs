@@ -93,8 +93,10 @@ impl ApiGroup {
         }
         let mut data = vec![];
         for vers in &g.versions {
-            let resources = client.list_api_group_resources(&vers.group_version).await?;
-            data.push(GroupVersionData::new(vers.version.clone(), resources)?);
+            let (resources, warnings) = client
+                .list_api_group_resources_with_warnings(&vers.group_version)
+                .await?;
+            data.push(GroupVersionData::new(vers.version.clone(), resources)?.with_warnings(&warnings));
         }
         let mut group = ApiGroup {
             name: key,
@@ -112,8 +114,8 @@ impl ApiGroup {
             return Err(Error::Discovery(DiscoveryError::EmptyApiGroup(key)));
         }
         for v in coreapis.versions {
-            let resources = client.list_core_api_resources(&v).await?;
-            data.push(GroupVersionData::new(v, resources)?);
+            let (resources, warnings) = client.list_core_api_resources_with_warnings(&v).await?;
+            data.push(GroupVersionData::new(v, resources)?.with_warnings(&warnings));
         }
         let mut group = ApiGroup {
             name: ApiGroup::CORE_GROUP.to_string(),
@@ -135,17 +137,18 @@ impl ApiGroup {
         gvk: &GroupVersionKind,
     ) -> Result<(ApiResource, ApiCapabilities)> {
         let apiver = gvk.api_version();
-        let list = if gvk.group.is_empty() {
-            client.list_core_api_resources(&apiver).await?
+        let (list, warnings) = if gvk.group.is_empty() {
+            client.list_core_api_resources_with_warnings(&apiver).await?
         } else {
-            client.list_api_group_resources(&apiver).await?
+            client.list_api_group_resources_with_warnings(&apiver).await?
         };
         for res in &list.resources {
             if res.kind == gvk.kind && !res.name.contains('/') {
                 let ar = parse::parse_apiresource(res, &list.group_version).map_err(
                     |ParseGroupVersionError(s)| Error::Discovery(DiscoveryError::InvalidGroupVersion(s)),
                 )?;
-                let caps = parse::parse_apicapabilities(&list, &res.name)?;
+                let mut caps = parse::parse_apicapabilities(&list, &res.name)?;
+                caps.warning = parse::parse_warnings(&warnings, &ar);
                 return Ok((ar, caps));
             }
         }
@@ -155,12 +158,12 @@ impl ApiGroup {
     // shortcut method to give cheapest return for a pinned group
     pub(crate) async fn query_gv(client: &Client, gv: &GroupVersion) -> Result<Self> {
         let apiver = gv.api_version();
-        let list = if gv.group.is_empty() {
-            client.list_core_api_resources(&apiver).await?
+        let (list, warnings) = if gv.group.is_empty() {
+            client.list_core_api_resources_with_warnings(&apiver).await?
         } else {
-            client.list_api_group_resources(&apiver).await?
+            client.list_api_group_resources_with_warnings(&apiver).await?
         };
-        let data = GroupVersionData::new(gv.version.clone(), list)?;
+        let data = GroupVersionData::new(gv.version.clone(), list)?.with_warnings(&warnings);
         let group = ApiGroup {
             name: gv.group.clone(),
             data: vec![data],
@@ -327,7 +330,52 @@ impl ApiGroup {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::client::Body;
+    use http::{Request, Response};
     use kube_core::discovery::Scope;
+    use std::pin::pin;
+    use tower_test::mock;
+
+    #[tokio::test]
+    async fn discovery_captures_warning_headers() {
+        let (mock_service, handle) = mock::pair::<Request<Body>, Response<Body>>();
+        let spawned = tokio::spawn(async move {
+            let mut handle = pin!(handle);
+            let (request, send) = handle.next_request().await.expect("service not called");
+            assert_eq!(request.uri().to_string(), "/apis/policy/v1beta1");
+            let list = serde_json::json!({
+                "kind": "APIResourceList",
+                "apiVersion": "v1",
+                "groupVersion": "policy/v1beta1",
+                "resources": [{
+                    "name": "podsecuritypolicies",
+                    "singularName": "podsecuritypolicy",
+                    "namespaced": false,
+                    "kind": "PodSecurityPolicy",
+                    "verbs": ["get", "list"]
+                }]
+            });
+            send.send_response(
+                Response::builder()
+                    .header(
+                        http::header::WARNING,
+                        r#"299 - "policy/v1beta1 PodSecurityPolicy is deprecated in v1.21+, unavailable in v1.25+""#,
+                    )
+                    .body(Body::from(serde_json::to_vec(&list).unwrap()))
+                    .unwrap(),
+            );
+        });
+
+        let client = Client::new(mock_service, "default");
+        let gvk = GroupVersionKind::gvk("policy", "v1beta1", "PodSecurityPolicy");
+        let (ar, caps) = ApiGroup::query_gvk(&client, &gvk).await.unwrap();
+        assert_eq!(ar.plural, "podsecuritypolicies");
+        assert_eq!(
+            caps.deprecation_warning(),
+            Some("policy/v1beta1 PodSecurityPolicy is deprecated in v1.21+, unavailable in v1.25+")
+        );
+        spawned.await.unwrap();
+    }
 
     #[test]
     fn test_resources_by_stability() {
@@ -335,6 +383,7 @@ mod tests {
             scope: Scope::Namespaced,
             subresources: vec![],
             operations: vec![],
+            warning: None,
         };
 
         let testlowversioncr_v1alpha1 = ApiResource {
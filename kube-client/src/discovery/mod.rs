@@ -1,16 +1,22 @@
 //! High-level utilities for runtime API discovery.
 
-use crate::{Client, Result};
-pub use kube_core::discovery::{verbs, ApiCapabilities, ApiResource, Scope};
+use crate::{error::DiscoveryError, Client, Result};
+pub use kube_core::discovery::{
+    diff_discovery, partition_by_scope, verbs, ApiCapabilities, ApiResource, DiscoveryDiff, Operations, Scope,
+};
 use kube_core::gvk::GroupVersionKind;
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
 mod apigroup;
 pub mod oneshot;
 pub use apigroup::ApiGroup;
 mod parse;
+pub use parse::from_apiresourcelist_tagged;
 
 // re-export one-shots
-pub use oneshot::{group, pinned_group, pinned_kind};
+pub use oneshot::{group, pinned_group, pinned_kind, resolve_resource};
 
 /// How the Discovery client decides what api groups to scan
 enum DiscoveryMode {
@@ -53,18 +59,38 @@ pub struct Discovery {
     client: Client,
     groups: HashMap<String, ApiGroup>,
     mode: DiscoveryMode,
+    min_refresh_interval: Duration,
+    last_refresh: Option<Instant>,
 }
 
 /// Caching discovery interface
 ///
 /// Builds an internal map of its cache
 impl Discovery {
+    /// Default minimum interval between refreshes from [`Discovery::force_refresh`]
+    pub const DEFAULT_MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
     /// Construct a caching api discovery client
     #[must_use]
     pub fn new(client: Client) -> Self {
         let groups = HashMap::new();
         let mode = DiscoveryMode::Block(vec![]);
-        Self { client, groups, mode }
+        Self {
+            client,
+            groups,
+            mode,
+            min_refresh_interval: Self::DEFAULT_MIN_REFRESH_INTERVAL,
+            last_refresh: None,
+        }
+    }
+
+    /// Configure the minimum interval between refreshes from [`Discovery::force_refresh`]
+    ///
+    /// Refreshes requested sooner than this after the previous one are skipped, and the cache is kept.
+    #[must_use]
+    pub fn min_refresh_interval(mut self, interval: Duration) -> Self {
+        self.min_refresh_interval = interval;
+        self
     }
 
     /// Configure the discovery client to only look for the listed apigroups
@@ -83,7 +109,7 @@ impl Discovery {
 
     /// Runs or re-runs the configured discovery algorithm and updates/populates the cache
     ///
-    /// The cache is empty cleared when this is started. By default, every api group found is checked,
+    /// By default, every api group found is checked,
     /// causing `N+2` queries to the api server (where `N` is number of api groups).
     ///
     /// ```no_run
@@ -109,14 +135,39 @@ impl Discovery {
     /// ```
     /// See a bigger example in [examples/dynamic.api](https://github.com/kube-rs/kube/blob/main/examples/dynamic_api.rs)
     pub async fn run(mut self) -> Result<Self> {
-        self.groups.clear();
+        self.refresh().await?;
+        Ok(self)
+    }
+
+    /// Re-runs discovery in place, unless the previous run was too recent
+    ///
+    /// Returns whether a refresh happened. Calls within the configured
+    /// [`min_refresh_interval`](Discovery::min_refresh_interval) of the previous attempt are coalesced into it,
+    /// leaving the cache untouched, to protect the apiserver from repeated invalidations.
+    /// Failed attempts count too, so a struggling apiserver is not hammered with retries.
+    ///
+    /// The cache is replaced once discovery completes, and left untouched if it fails.
+    pub async fn force_refresh(&mut self) -> Result<bool> {
+        if let Some(last) = self.last_refresh {
+            if last.elapsed() < self.min_refresh_interval {
+                return Ok(false);
+            }
+        }
+        self.refresh().await?;
+        Ok(true)
+    }
+
+    // Only replaces the cache once every query succeeded, so a failed refresh keeps the previous results
+    async fn refresh(&mut self) -> Result<()> {
+        self.last_refresh = Some(Instant::now());
+        let mut groups = HashMap::new();
         let api_groups = self.client.list_api_groups().await?;
         // query regular groups + crds under /apis
         for g in api_groups.groups {
             let key = g.name.clone();
             if self.mode.is_queryable(&key) {
                 let apigroup = ApiGroup::query_apis(&self.client, g).await?;
-                self.groups.insert(key, apigroup);
+                groups.insert(key, apigroup);
             }
         }
         // query core versions under /api
@@ -124,9 +175,10 @@ impl Discovery {
         if self.mode.is_queryable(&corekey) {
             let coreapis = self.client.list_core_api_versions().await?;
             let apigroup = ApiGroup::query_core(&self.client, coreapis).await?;
-            self.groups.insert(corekey, apigroup);
+            groups.insert(corekey, apigroup);
         }
-        Ok(self)
+        self.groups = groups;
+        Ok(())
     }
 }
 
@@ -168,3 +220,205 @@ impl Discovery {
             .find(|res| res.0.kind == gvk.kind)
     }
 }
+
+/// A lightweight map of resource scopes by group, version and kind
+///
+/// Populate it from a complete [`Discovery`], or [`insert`](ScopeCache::insert) known scopes by hand.
+/// Given to a [`Client`] with [`Client::with_scope_cache`], it lets [`Api::namespaced_with`](crate::Api::namespaced_with)
+/// warn when a cluster scoped resource is used within a namespace.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(docsrs, doc(cfg(feature = "client")))]
+pub struct ScopeCache {
+    scopes: HashMap<GroupVersionKind, Scope>,
+}
+
+impl ScopeCache {
+    /// An empty cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the scope of a resource
+    pub fn insert(&mut self, gvk: GroupVersionKind, scope: Scope) {
+        self.scopes.insert(gvk, scope);
+    }
+
+    /// The scope of a resource, if it is known
+    pub fn scope_of(&self, gvk: &GroupVersionKind) -> Option<Scope> {
+        self.scopes.get(gvk).cloned()
+    }
+
+    /// Check that a resource can be used within a namespace
+    ///
+    /// Fails with [`DiscoveryError::ScopeMismatch`] only when the resource is known to be cluster scoped.
+    pub fn check_namespaced(&self, gvk: &GroupVersionKind) -> Result<(), DiscoveryError> {
+        match self.scope_of(gvk) {
+            Some(Scope::Cluster) => Err(DiscoveryError::ScopeMismatch(format!(
+                "{}/{}",
+                gvk.api_version(),
+                gvk.kind
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl From<&Discovery> for ScopeCache {
+    fn from(discovery: &Discovery) -> Self {
+        let mut cache = ScopeCache::new();
+        for group in discovery.groups() {
+            for version in group.versions() {
+                for (ar, caps) in group.versioned_resources(version) {
+                    let gvk = GroupVersionKind::gvk(&ar.group, &ar.version, &ar.kind);
+                    cache.insert(gvk, caps.scope);
+                }
+            }
+        }
+        cache
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::Body;
+    use http::{Request, Response};
+    use std::pin::pin;
+    use tower_test::mock;
+
+    #[tokio::test]
+    async fn force_refresh_is_rate_limited() {
+        let (mock_service, handle) = mock::pair::<Request<Body>, Response<Body>>();
+        let spawned = tokio::spawn(async move {
+            let mut handle = pin!(handle);
+            let (request, send) = handle.next_request().await.expect("service not called");
+            assert_eq!(request.uri().to_string(), "/apis");
+            let groups = serde_json::json!({ "kind": "APIGroupList", "apiVersion": "v1", "groups": [] });
+            send.send_response(
+                Response::builder()
+                    .body(Body::from(groups.to_string().into_bytes()))
+                    .unwrap(),
+            );
+            // no further fetches
+            assert!(handle.next_request().await.is_none());
+        });
+
+        // an empty filter only lists the groups under /apis
+        let mut discovery = Discovery::new(Client::new(mock_service, "default"))
+            .filter(&[])
+            .min_refresh_interval(Duration::from_secs(60));
+        assert!(discovery.force_refresh().await.unwrap());
+        assert!(!discovery.force_refresh().await.unwrap());
+        drop(discovery);
+        spawned.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn failed_refresh_keeps_cached_groups() {
+        let (mock_service, handle) = mock::pair::<Request<Body>, Response<Body>>();
+        let spawned = tokio::spawn(async move {
+            let mut handle = pin!(handle);
+            let (request, send) = handle.next_request().await.expect("service not called");
+            assert_eq!(request.uri().to_string(), "/apis");
+            let groups = serde_json::json!({
+                "kind": "APIGroupList",
+                "apiVersion": "v1",
+                "groups": [{
+                    "name": "apps",
+                    "versions": [{ "groupVersion": "apps/v1", "version": "v1" }],
+                    "preferredVersion": { "groupVersion": "apps/v1", "version": "v1" },
+                }],
+            });
+            send.send_response(
+                Response::builder()
+                    .body(Body::from(groups.to_string().into_bytes()))
+                    .unwrap(),
+            );
+
+            let (request, send) = handle.next_request().await.expect("service not called");
+            assert_eq!(request.uri().to_string(), "/apis/apps/v1");
+            let resources = serde_json::json!({
+                "kind": "APIResourceList",
+                "apiVersion": "v1",
+                "groupVersion": "apps/v1",
+                "resources": [{
+                    "name": "deployments",
+                    "singularName": "deployment",
+                    "namespaced": true,
+                    "kind": "Deployment",
+                    "verbs": ["get", "list"],
+                }],
+            });
+            send.send_response(
+                Response::builder()
+                    .body(Body::from(resources.to_string().into_bytes()))
+                    .unwrap(),
+            );
+
+            // the second refresh fails on its first query
+            let (request, send) = handle.next_request().await.expect("service not called");
+            assert_eq!(request.uri().to_string(), "/apis");
+            send.send_response(
+                Response::builder()
+                    .status(http::StatusCode::INTERNAL_SERVER_ERROR)
+                    .body(Body::empty())
+                    .unwrap(),
+            );
+        });
+
+        let mut discovery = Discovery::new(Client::new(mock_service, "default"))
+            .filter(&["apps"])
+            .min_refresh_interval(Duration::ZERO);
+        assert!(discovery.force_refresh().await.unwrap());
+        assert!(discovery.force_refresh().await.is_err());
+        let gvk = GroupVersionKind::gvk("apps", "v1", "Deployment");
+        assert!(discovery.resolve_gvk(&gvk).is_some());
+        drop(discovery);
+        spawned.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn failed_refresh_is_rate_limited() {
+        let (mock_service, handle) = mock::pair::<Request<Body>, Response<Body>>();
+        let spawned = tokio::spawn(async move {
+            let mut handle = pin!(handle);
+            let (request, send) = handle.next_request().await.expect("service not called");
+            assert_eq!(request.uri().to_string(), "/apis");
+            send.send_response(
+                Response::builder()
+                    .status(http::StatusCode::INTERNAL_SERVER_ERROR)
+                    .body(Body::empty())
+                    .unwrap(),
+            );
+            // no retries within the interval
+            assert!(handle.next_request().await.is_none());
+        });
+
+        let mut discovery = Discovery::new(Client::new(mock_service, "default"))
+            .filter(&[])
+            .min_refresh_interval(Duration::from_secs(60));
+        assert!(discovery.force_refresh().await.is_err());
+        assert!(!discovery.force_refresh().await.unwrap());
+        drop(discovery);
+        spawned.await.unwrap();
+    }
+
+    #[test]
+    fn scope_cache_flags_namespaced_use_of_cluster_resources() {
+        let node = GroupVersionKind::gvk("", "v1", "Node");
+        let pod = GroupVersionKind::gvk("", "v1", "Pod");
+        let unknown = GroupVersionKind::gvk("clux.dev", "v1", "Foo");
+        let mut cache = ScopeCache::new();
+        cache.insert(node.clone(), Scope::Cluster);
+        cache.insert(pod.clone(), Scope::Namespaced);
+
+        assert_eq!(cache.scope_of(&node), Some(Scope::Cluster));
+        assert_eq!(cache.scope_of(&unknown), None);
+        assert!(matches!(
+            cache.check_namespaced(&node),
+            Err(DiscoveryError::ScopeMismatch(gvk)) if gvk == "v1/Node"
+        ));
+        assert!(cache.check_namespaced(&pod).is_ok());
+        assert!(cache.check_namespaced(&unknown).is_ok());
+    }
+}
@@ -7,15 +7,22 @@
 //! - all kinds in a group at pinned version: "apiregistration.k8s.io/v1" via [`oneshot::pinned_group`]
 //! - all kinds/version combinations in a group: "apiregistration.k8s.io" via [`oneshot::group`]
 //!
+//! It also resolves user input like `deploy` to a ready [`Api`](crate::Api) via [`Client::api_for`].
+//!
 //! [`oneshot::group`]: crate::discovery::group
 //! [`oneshot::pinned_group`]: crate::discovery::pinned_group
 //! [`oneshot::pinned_kind`]: crate::discovery::pinned_kind
 
-use super::ApiGroup;
-use crate::{error::DiscoveryError, Client, Error, Result};
+use super::{
+    parse::{parse_apicapabilities, parse_apiresource},
+    ApiGroup,
+};
+use crate::{error::DiscoveryError, Api, Client, Error, Result};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{APIResource, APIResourceList};
 use kube_core::{
-    discovery::{ApiCapabilities, ApiResource},
-    gvk::{GroupVersion, GroupVersionKind},
+    discovery::{ApiCapabilities, ApiResource, Scope},
+    dynamic::DynamicObject,
+    gvk::{GroupVersion, GroupVersionKind, ParseGroupVersionError},
 };
 
 /// Discovers all APIs available under a certain group at all versions
@@ -104,3 +111,159 @@ pub async fn pinned_group(client: &Client, gv: &GroupVersion) -> Result<ApiGroup
 pub async fn pinned_kind(client: &Client, gvk: &GroupVersionKind) -> Result<(ApiResource, ApiCapabilities)> {
     ApiGroup::query_gvk(client, gvk).await
 }
+
+/// Resolves a resource from user input such as `deployments`, `deployment`, `deploy` or `Deployment`
+///
+/// Matches plural names, singular names, short names and kinds case-insensitively,
+/// checking the core group first and then the preferred version of every other group, like `kubectl get`.
+pub async fn resolve_resource(
+    client: &Client,
+    resource_token: &str,
+) -> Result<(ApiResource, ApiCapabilities)> {
+    if resource_token.is_empty() {
+        return Err(Error::Discovery(DiscoveryError::MissingResource(String::new())));
+    }
+    let token = resource_token.to_ascii_lowercase();
+    let core = client.list_core_api_versions().await?;
+    for version in &core.versions {
+        let list = client.list_core_api_resources(version).await?;
+        if let Some(found) = find_resource(&list, &token)? {
+            return Ok(found);
+        }
+    }
+    let api_groups = client.list_api_groups().await?;
+    for g in api_groups.groups {
+        let Some(gv) = g.preferred_version.or_else(|| g.versions.into_iter().next()) else {
+            continue;
+        };
+        let list = client.list_api_group_resources(&gv.group_version).await?;
+        if let Some(found) = find_resource(&list, &token)? {
+            return Ok(found);
+        }
+    }
+    Err(Error::Discovery(DiscoveryError::MissingResource(
+        resource_token.to_string(),
+    )))
+}
+
+fn find_resource(list: &APIResourceList, token: &str) -> Result<Option<(ApiResource, ApiCapabilities)>> {
+    let matches = |res: &APIResource| {
+        res.name == token
+            || (!res.singular_name.is_empty() && res.singular_name == token)
+            || res.kind.to_ascii_lowercase() == token
+            || res.short_names.iter().flatten().any(|s| s == token)
+    };
+    let Some(res) = list
+        .resources
+        .iter()
+        .find(|r| !r.name.contains('/') && matches(r))
+    else {
+        return Ok(None);
+    };
+    let ar = parse_apiresource(res, &list.group_version)
+        .map_err(|ParseGroupVersionError(s)| Error::Discovery(DiscoveryError::InvalidGroupVersion(s)))?;
+    let caps = parse_apicapabilities(list, &res.name)?;
+    Ok(Some((ar, caps)))
+}
+
+impl Client {
+    /// Creates an `Api<DynamicObject>` from user input such as `deploy`, resolved through discovery
+    ///
+    /// See [`resolve_resource`] for how the `resource_token` is matched.
+    /// The `namespace` is ignored for cluster scoped resources, and namespaced resources without
+    /// a `namespace` are addressed across all namespaces.
+    ///
+    /// ```no_run
+    /// use kube::{Client, api::{Api, DynamicObject}, ResourceExt};
+    /// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = Client::try_default().await?;
+    /// let api: Api<DynamicObject> = client.api_for("deploy", Some("default")).await?;
+    /// for deploy in api.list(&Default::default()).await? {
+    ///     println!("Found Deployment: {}", deploy.name_any());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn api_for(&self, resource_token: &str, namespace: Option<&str>) -> Result<Api<DynamicObject>> {
+        let (ar, caps) = resolve_resource(self, resource_token).await?;
+        Ok(match (caps.scope, namespace) {
+            (Scope::Namespaced, Some(ns)) => Api::namespaced_with(self.clone(), ns, &ar),
+            _ => Api::all_with(self.clone(), &ar),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{client::Body, error::DiscoveryError, Client, Error};
+    use http::{Request, Response};
+    use std::pin::pin;
+    use tower_test::mock;
+
+    #[tokio::test]
+    async fn api_for_resolves_short_names() {
+        let (mock_service, handle) = mock::pair::<Request<Body>, Response<Body>>();
+        let spawned = tokio::spawn(async move {
+            let mut handle = pin!(handle);
+            for _ in 0..4 {
+                let (request, send) = handle.next_request().await.expect("service not called");
+                let body = match request.uri().path() {
+                    "/api" => serde_json::json!({
+                        "kind": "APIVersions",
+                        "apiVersion": "v1",
+                        "versions": ["v1"],
+                        "serverAddressByClientCIDRs": [],
+                    }),
+                    "/api/v1" => serde_json::json!({
+                        "kind": "APIResourceList",
+                        "apiVersion": "v1",
+                        "groupVersion": "v1",
+                        "resources": [
+                            { "name": "pods", "singularName": "pod", "namespaced": true, "kind": "Pod", "verbs": ["list"], "shortNames": ["po"] },
+                        ]
+                    }),
+                    "/apis" => serde_json::json!({
+                        "kind": "APIGroupList",
+                        "apiVersion": "v1",
+                        "groups": [{
+                            "name": "apps",
+                            "versions": [{ "groupVersion": "apps/v1", "version": "v1" }],
+                            "preferredVersion": { "groupVersion": "apps/v1", "version": "v1" },
+                        }]
+                    }),
+                    "/apis/apps/v1" => serde_json::json!({
+                        "kind": "APIResourceList",
+                        "apiVersion": "v1",
+                        "groupVersion": "apps/v1",
+                        "resources": [
+                            { "name": "deployments", "singularName": "deployment", "namespaced": true, "kind": "Deployment", "verbs": ["list"], "shortNames": ["deploy"] },
+                            { "name": "deployments/scale", "singularName": "", "namespaced": true, "group": "autoscaling", "version": "v1", "kind": "Scale", "verbs": ["get"] },
+                        ]
+                    }),
+                    path => panic!("unexpected request to {path}"),
+                };
+                send.send_response(
+                    Response::builder()
+                        .body(Body::from(body.to_string().into_bytes()))
+                        .unwrap(),
+                );
+            }
+        });
+
+        let client = Client::new(mock_service, "default");
+        let api = client.api_for("deploy", Some("apps")).await.unwrap();
+        assert_eq!(api.resource_url(), "/apis/apps/v1/namespaces/apps/deployments");
+        spawned.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn resolve_resource_rejects_empty_token() {
+        let (mock_service, _handle) = mock::pair::<Request<Body>, Response<Body>>();
+        let client = Client::new(mock_service, "default");
+        let err = super::resolve_resource(&client, "").await.unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Discovery(DiscoveryError::MissingResource(token)) if token.is_empty()
+        ));
+    }
+}
@@ -3,7 +3,7 @@ use crate::{error::DiscoveryError, Error, Result};
 use k8s_openapi::apimachinery::pkg::apis::meta::v1::{APIResource, APIResourceList};
 use kube_core::{
     discovery::{ApiCapabilities, ApiResource, Scope},
-    gvk::{GroupVersion, ParseGroupVersionError},
+    gvk::ParseGroupVersionError,
 };
 
 /// Creates an `ApiResource` from a `meta::v1::APIResource` instance + its groupversion.
@@ -13,15 +13,26 @@ pub(crate) fn parse_apiresource(
     ar: &APIResource,
     group_version: &str,
 ) -> Result<ApiResource, ParseGroupVersionError> {
-    let gv: GroupVersion = group_version.parse()?;
     // NB: not safe to use this with subresources (they don't have api_versions)
-    Ok(ApiResource {
-        group: ar.group.clone().unwrap_or_else(|| gv.group.clone()),
-        version: ar.version.clone().unwrap_or_else(|| gv.version.clone()),
-        api_version: gv.api_version(),
-        kind: ar.kind.to_string(),
-        plural: ar.name.clone(),
-    })
+    ApiResource::from_apiresource(ar, group_version)
+}
+
+/// Creates an `ApiResource` for every top-level resource in an `APIResourceList`.
+///
+/// Each resource is paired with the `group_version` of the list it came from,
+/// so flattened results from several lists can still be traced back for re-discovery.
+/// Subresources are skipped.
+pub fn from_apiresourcelist_tagged(list: &APIResourceList) -> Result<Vec<(ApiResource, String)>> {
+    list.resources
+        .iter()
+        .filter(|res| !res.name.contains('/'))
+        .map(|res| {
+            let ar = parse_apiresource(res, &list.group_version).map_err(|ParseGroupVersionError(s)| {
+                Error::Discovery(DiscoveryError::InvalidGroupVersion(s))
+            })?;
+            Ok((ar, list.group_version.clone()))
+        })
+        .collect()
 }
 
 /// Creates `ApiCapabilities` from a `meta::v1::APIResourceList` instance + a name from the list.
@@ -56,9 +67,42 @@ pub(crate) fn parse_apicapabilities(list: &APIResourceList, name: &str) -> Resul
         scope,
         subresources,
         operations: ar.verbs.clone(),
+        warning: None,
     })
 }
 
+/// Extracts the text of the `Warning` headers that name a resource into a single warning message.
+///
+/// Warning headers are of the form `299 - "some message"`; the quoted text is kept.
+/// The headers of a discovery response cover its whole group version, so only warnings mentioning
+/// the kind or plural name of `ar` as a word are kept, e.g. a PodSecurityPolicy deprecation
+/// does not mark PodDisruptionBudgets in `policy/v1beta1` as deprecated.
+pub(crate) fn parse_warnings(warnings: &[String], ar: &ApiResource) -> Option<String> {
+    let texts = warnings
+        .iter()
+        .map(|w| match (w.find('"'), w.rfind('"')) {
+            (Some(start), Some(end)) if end > start => &w[start + 1..end],
+            _ => w.as_str(),
+        })
+        .filter(|text| contains_word(text, &ar.kind) || contains_word(text, &ar.plural))
+        .collect::<Vec<_>>();
+    if texts.is_empty() {
+        None
+    } else {
+        Some(texts.join("; "))
+    }
+}
+
+// Whether `word` occurs in `text` without being part of a longer alphanumeric word
+fn contains_word(text: &str, word: &str) -> bool {
+    !word.is_empty()
+        && text.match_indices(word).any(|(idx, _)| {
+            let before = text[..idx].chars().next_back();
+            let after = text[idx + word.len()..].chars().next();
+            !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+        })
+}
+
 /// Internal resource information and capabilities for a particular ApiGroup at a particular version
 pub(crate) struct GroupVersionData {
     /// Pinned api version
@@ -85,4 +129,65 @@ impl GroupVersionData {
         }
         Ok(GroupVersionData { version, resources })
     }
+
+    /// Attach the `Warning` headers returned alongside the APIResourceList to the resources they name
+    pub(crate) fn with_warnings(mut self, warnings: &[String]) -> Self {
+        for (ar, caps) in &mut self.resources {
+            caps.warning = parse_warnings(warnings, ar);
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tagged_resources_carry_source_group_version() {
+        let list: APIResourceList = serde_json::from_value(serde_json::json!({
+            "groupVersion": "apps/v1",
+            "resources": [
+                { "name": "deployments", "singularName": "", "namespaced": true, "kind": "Deployment", "verbs": ["get"] },
+                { "name": "deployments/scale", "singularName": "", "namespaced": true, "group": "autoscaling", "version": "v1", "kind": "Scale", "verbs": ["get"] },
+                { "name": "daemonsets", "singularName": "", "namespaced": true, "kind": "DaemonSet", "verbs": ["get"] }
+            ]
+        }))
+        .unwrap();
+        let tagged = from_apiresourcelist_tagged(&list).unwrap();
+        assert_eq!(tagged.len(), 2);
+        assert_eq!(tagged[0].0.kind, "Deployment");
+        assert_eq!(tagged[1].0.plural, "daemonsets");
+        assert!(tagged
+            .iter()
+            .all(|(ar, gv)| gv == "apps/v1" && ar.api_version == *gv));
+    }
+
+    #[test]
+    fn warnings_only_attach_to_named_resources() {
+        let list: APIResourceList = serde_json::from_value(serde_json::json!({
+            "groupVersion": "policy/v1beta1",
+            "resources": [
+                { "name": "podsecuritypolicies", "singularName": "", "namespaced": false, "kind": "PodSecurityPolicy", "verbs": ["get"] },
+                { "name": "poddisruptionbudgets", "singularName": "", "namespaced": true, "kind": "PodDisruptionBudget", "verbs": ["get"] }
+            ]
+        }))
+        .unwrap();
+        let warnings = [
+            r#"299 - "policy/v1beta1 PodSecurityPolicy is deprecated in v1.21+, unavailable in v1.25+""#
+                .into(),
+        ];
+        let data = GroupVersionData::new("v1beta1".into(), list)
+            .unwrap()
+            .with_warnings(&warnings);
+        let warning_of = |kind: &str| {
+            let (_, caps) = data.resources.iter().find(|(ar, _)| ar.kind == kind).unwrap();
+            caps.warning.clone()
+        };
+        assert_eq!(
+            warning_of("PodSecurityPolicy").as_deref(),
+            Some("policy/v1beta1 PodSecurityPolicy is deprecated in v1.21+, unavailable in v1.25+")
+        );
+        assert_eq!(warning_of("PodDisruptionBudget"), None);
+    }
 }
@@ -132,4 +132,8 @@ pub enum DiscoveryError {
     /// Empty ApiGroup
     #[error("Empty Api Group: {0}")]
     EmptyApiGroup(String),
+
+    /// A cluster scoped resource was used within a namespace
+    #[error("Scope mismatch: {0} is cluster scoped")]
+    ScopeMismatch(String),
 }
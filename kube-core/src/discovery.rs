@@ -1,6 +1,13 @@
 //! Type information structs for API discovery
-use crate::{gvk::GroupVersionKind, resource::Resource};
+use crate::{
+    gvk::{GroupVersion, GroupVersionKind, ParseGroupVersionError},
+    params::{Patch, PatchParams},
+    request::{self, Request},
+    resource::Resource,
+};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::APIResource;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Information about a Kubernetes API resource
 ///
@@ -53,7 +60,90 @@ impl ApiResource {
     /// Otherwise consider using [`ApiResource::from_gvk_with_plural`](crate::discovery::ApiResource::from_gvk_with_plural)
     /// to explicitly set the plural, or run api discovery on it via `kube::discovery`.
     pub fn from_gvk(gvk: &GroupVersionKind) -> Self {
-        ApiResource::from_gvk_with_plural(gvk, &to_plural(&gvk.kind.to_ascii_lowercase()))
+        ApiResource::from_gvk_with(gvk, to_plural)
+    }
+
+    /// Creates an ApiResource from group, version and kind, pluralised by `infer`
+    ///
+    /// `infer` is given the lowercased kind, and returns its plural name.
+    /// This allows using a proper inflector instead of the built-in guess of [`ApiResource::from_gvk`].
+    pub fn from_gvk_with(gvk: &GroupVersionKind, infer: impl Fn(&str) -> String) -> Self {
+        ApiResource::from_gvk_with_plural(gvk, &infer(&gvk.kind.to_ascii_lowercase()))
+    }
+
+    /// Creates an ApiResource from a discovered `meta::v1::APIResource` and the group version of its list.
+    ///
+    /// The group and version of the `APIResource` take precedence when set, as for subresources,
+    /// which may belong to a different group than their list.
+    pub fn from_apiresource(ar: &APIResource, group_version: &str) -> Result<Self, ParseGroupVersionError> {
+        let gv: GroupVersion = group_version.parse()?;
+        Ok(ApiResource {
+            group: ar.group.clone().unwrap_or_else(|| gv.group.clone()),
+            version: ar.version.clone().unwrap_or_else(|| gv.version.clone()),
+            api_version: gv.api_version(),
+            kind: ar.kind.to_string(),
+            plural: ar.name.clone(),
+        })
+    }
+
+    /// Creates an ApiResource from group, version and kind, paired with capabilities of the given scope.
+    ///
+    /// This lets the scope travel with the resource when it is known without running discovery.
+    /// The returned capabilities list no operations or subresources.
+    /// Like [`ApiResource::from_gvk`], this will **guess** the resource plural name.
+    pub fn from_gvk_with_scope(gvk: &GroupVersionKind, scope: Scope) -> (Self, ApiCapabilities) {
+        let caps = ApiCapabilities {
+            scope,
+            subresources: vec![],
+            operations: vec![],
+            warning: None,
+        };
+        (ApiResource::from_gvk(gvk), caps)
+    }
+
+    /// Returns the url path of the collection of this resource, used for list and create calls.
+    ///
+    /// Pass a `namespace` to address the collection within that namespace.
+    pub fn collection_url(&self, namespace: Option<&str>) -> String {
+        let prefix = if self.group.is_empty() { "api" } else { "apis" };
+        let namespaces = namespace
+            .map(|ns| format!("namespaces/{ns}/"))
+            .unwrap_or_default();
+        format!("/{prefix}/{}/{namespaces}{}", self.api_version, self.plural)
+    }
+
+    /// Returns the url path of a single named object of this resource, used for get, replace, patch and delete calls.
+    pub fn item_url(&self, namespace: Option<&str>, name: &str) -> String {
+        format!("{}/{name}", self.collection_url(namespace))
+    }
+
+    /// Builds a merge patch request setting `spec.replicas` through the `scale` subresource
+    ///
+    /// This works for any resource exposing `scale`, such as deployments or scalable custom resources.
+    pub fn scale_patch_request(
+        &self,
+        namespace: Option<&str>,
+        name: &str,
+        replicas: i32,
+    ) -> Result<http::Request<Vec<u8>>, request::Error> {
+        let patch = Patch::Merge(serde_json::json!({ "spec": { "replicas": replicas } }));
+        Request::new(self.collection_url(namespace)).patch_subresource(
+            "scale",
+            name,
+            &PatchParams::default(),
+            &patch,
+        )
+    }
+
+    /// Returns the discovery url path listing the resources of this resource's group version.
+    ///
+    /// This is `/api/{version}` for the core group and `/apis/{group}/{version}` otherwise.
+    pub fn discovery_url(&self) -> String {
+        if self.group.is_empty() {
+            format!("/api/{}", self.version)
+        } else {
+            format!("/apis/{}/{}", self.group, self.version)
+        }
     }
 }
 
@@ -86,6 +176,121 @@ pub mod verbs {
     pub const PATCH: &str = "patch";
 }
 
+/// Typed set of the operations supported by an API resource
+///
+/// Built from the verb list in [`ApiCapabilities::operations`].
+///
+/// Serializes its fields in declaration order, with `other` sorted,
+/// so equal operation sets always produce identical output.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Operations {
+    /// Supports [`verbs::CREATE`]
+    pub create: bool,
+    /// Supports [`verbs::GET`]
+    pub get: bool,
+    /// Supports [`verbs::LIST`]
+    pub list: bool,
+    /// Supports [`verbs::WATCH`]
+    pub watch: bool,
+    /// Supports [`verbs::DELETE`]
+    pub delete: bool,
+    /// Supports [`verbs::DELETE_COLLECTION`]
+    pub delete_collection: bool,
+    /// Supports [`verbs::UPDATE`]
+    pub update: bool,
+    /// Supports [`verbs::PATCH`]
+    pub patch: bool,
+    /// Any other verbs, such as `proxy` or custom verbs
+    #[serde(default, serialize_with = "serialize_sorted")]
+    pub other: Vec<String>,
+}
+
+fn serialize_sorted<S: serde::Serializer>(verbs: &[String], serializer: S) -> Result<S::Ok, S::Error> {
+    let mut sorted = verbs.iter().collect::<Vec<_>>();
+    sorted.sort();
+    serializer.collect_seq(sorted)
+}
+
+impl Operations {
+    /// Operations supported by either `self` or `other`
+    ///
+    /// Useful to reason about what is possible in any version of a resource.
+    #[must_use]
+    pub fn union(&self, other: &Operations) -> Operations {
+        let mut verbs = self.other.clone();
+        for verb in &other.other {
+            if !verbs.contains(verb) {
+                verbs.push(verb.clone());
+            }
+        }
+        Operations {
+            create: self.create || other.create,
+            get: self.get || other.get,
+            list: self.list || other.list,
+            watch: self.watch || other.watch,
+            delete: self.delete || other.delete,
+            delete_collection: self.delete_collection || other.delete_collection,
+            update: self.update || other.update,
+            patch: self.patch || other.patch,
+            other: verbs,
+        }
+    }
+
+    /// Operations supported by both `self` and `other`
+    #[must_use]
+    pub fn intersect(&self, other: &Operations) -> Operations {
+        Operations {
+            create: self.create && other.create,
+            get: self.get && other.get,
+            list: self.list && other.list,
+            watch: self.watch && other.watch,
+            delete: self.delete && other.delete,
+            delete_collection: self.delete_collection && other.delete_collection,
+            update: self.update && other.update,
+            patch: self.patch && other.patch,
+            other: self
+                .other
+                .iter()
+                .filter(|verb| other.other.contains(verb))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Operations both supported by a resource and permitted by the `allowed_verbs` of a user
+    ///
+    /// The `allowed_verbs` are RBAC verbs, e.g. from a `SelfSubjectRulesReview`, where `*` permits every verb.
+    pub fn effective(resource: &ApiCapabilities, allowed_verbs: &[String]) -> Operations {
+        let supported = resource.typed_operations();
+        if allowed_verbs.iter().any(|verb| verb == "*") {
+            return supported;
+        }
+        supported.intersect(&Operations::from(allowed_verbs))
+    }
+}
+
+impl From<&[String]> for Operations {
+    fn from(operations: &[String]) -> Self {
+        let mut ops = Operations::default();
+        for op in operations {
+            match op.as_str() {
+                verbs::CREATE => ops.create = true,
+                verbs::GET => ops.get = true,
+                verbs::LIST => ops.list = true,
+                verbs::WATCH => ops.watch = true,
+                verbs::DELETE => ops.delete = true,
+                verbs::DELETE_COLLECTION => ops.delete_collection = true,
+                verbs::UPDATE => ops.update = true,
+                verbs::PATCH => ops.patch = true,
+                other if !ops.other.iter().any(|o| o == other) => ops.other.push(other.to_string()),
+                _ => {}
+            }
+        }
+        ops
+    }
+}
+
 /// Contains the capabilities of an API resource
 #[derive(Debug, Clone)]
 pub struct ApiCapabilities {
@@ -99,6 +304,10 @@ pub struct ApiCapabilities {
     pub subresources: Vec<(ApiResource, ApiCapabilities)>,
     /// Supported operations on this resource
     pub operations: Vec<String>,
+    /// Warning returned by the apiserver when this resource was discovered
+    ///
+    /// The apiserver uses `Warning` headers to flag deprecated apis.
+    pub warning: Option<String>,
 }
 
 impl ApiCapabilities {
@@ -106,10 +315,139 @@ impl ApiCapabilities {
     pub fn supports_operation(&self, operation: &str) -> bool {
         self.operations.iter().any(|op| op == operation)
     }
+
+    /// Whether objects of this resource live in a namespace
+    pub fn is_namespaced(&self) -> bool {
+        self.scope == Scope::Namespaced
+    }
+
+    /// Whether objects of this resource are global to the cluster
+    pub fn is_cluster_scoped(&self) -> bool {
+        self.scope == Scope::Cluster
+    }
+
+    /// Returns the supported operations as a typed [`Operations`] set.
+    pub fn typed_operations(&self) -> Operations {
+        Operations::from(self.operations.as_slice())
+    }
+
+    /// Returns the deprecation warning the apiserver sent while discovering this resource, if any.
+    pub fn deprecation_warning(&self) -> Option<&str> {
+        self.warning.as_deref()
+    }
+
+    /// Whether the `exec` subresource is available, as on `pods`
+    pub fn supports_exec(&self) -> bool {
+        self.supports_connect_subresource("exec")
+    }
+
+    /// Whether the `attach` subresource is available, as on `pods`
+    pub fn supports_attach(&self) -> bool {
+        self.supports_connect_subresource("attach")
+    }
+
+    /// Whether the `portforward` subresource is available, as on `pods`
+    pub fn supports_portforward(&self) -> bool {
+        self.supports_connect_subresource("portforward")
+    }
+
+    // Connect subresources are discovered with `create` and/or `get` verbs
+    fn supports_connect_subresource(&self, name: &str) -> bool {
+        self.subresources.iter().any(|(ar, caps)| {
+            ar.plural == name
+                && (caps.supports_operation(verbs::CREATE) || caps.supports_operation(verbs::GET))
+        })
+    }
+
+    /// Returns the full `parent/subresource` name of each subresource, e.g. `deployments/scale`
+    ///
+    /// This is the name the apiserver lists the subresource under in discovery.
+    pub fn full_subresource_names(&self, parent_plural: &str) -> Vec<String> {
+        self.subresource_paths(parent_plural)
+            .map(|(path, _)| path)
+            .collect()
+    }
+
+    /// Pairs each subresource's full path segment with its supported operations.
+    ///
+    /// Subresource names in [`ApiCapabilities::subresources`] lack their parent resource,
+    /// so the `parent` is used to reconstruct addressable segments like `deployments/scale`.
+    pub fn addressable_subresources(&self, parent: &ApiResource) -> Vec<(String, &[String])> {
+        self.subresource_paths(&parent.plural)
+            .map(|(path, caps)| (path, caps.operations.as_slice()))
+            .collect()
+    }
+
+    // Full `parent/subresource` path of each subresource along with its capabilities
+    fn subresource_paths<'a>(
+        &'a self,
+        parent_plural: &'a str,
+    ) -> impl Iterator<Item = (String, &'a ApiCapabilities)> + 'a {
+        self.subresources
+            .iter()
+            .map(move |(ar, caps)| (format!("{parent_plural}/{}", ar.plural), caps))
+    }
+}
+
+/// Splits discovered resources by their scope
+///
+/// Returns the namespaced resources first, followed by the cluster scoped resources.
+pub fn partition_by_scope(
+    resources: &[(ApiResource, ApiCapabilities)],
+) -> (Vec<ApiResource>, Vec<ApiResource>) {
+    let (namespaced, cluster): (Vec<_>, Vec<_>) = resources
+        .iter()
+        .partition(|(_, caps)| caps.scope == Scope::Namespaced);
+    (
+        namespaced.into_iter().map(|(ar, _)| ar.clone()).collect(),
+        cluster.into_iter().map(|(ar, _)| ar.clone()).collect(),
+    )
+}
+
+/// Changes between two discovery snapshots
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiscoveryDiff {
+    /// Resources only present in the new snapshot
+    pub added: Vec<ApiResource>,
+    /// Resources only present in the old snapshot
+    pub removed: Vec<ApiResource>,
+    /// Resources with the same group, version and kind whose details changed, as `(old, new)` pairs
+    pub changed: Vec<(ApiResource, ApiResource)>,
+}
+
+impl DiscoveryDiff {
+    /// Whether the two snapshots were equivalent
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Computes which resources appeared, disappeared or changed between two discovery snapshots
+///
+/// Resources are matched by group, version and kind.
+pub fn diff_discovery(old: &[ApiResource], new: &[ApiResource]) -> DiscoveryDiff {
+    let gvk = |ar: &ApiResource| (ar.group.clone(), ar.version.clone(), ar.kind.clone());
+    let old_by_gvk = old.iter().map(|ar| (gvk(ar), ar)).collect::<HashMap<_, _>>();
+    let new_by_gvk = new.iter().map(|ar| (gvk(ar), ar)).collect::<HashMap<_, _>>();
+
+    let mut diff = DiscoveryDiff::default();
+    for ar in new {
+        match old_by_gvk.get(&gvk(ar)) {
+            None => diff.added.push(ar.clone()),
+            Some(prev) if *prev != ar => diff.changed.push(((*prev).clone(), ar.clone())),
+            Some(_) => {}
+        }
+    }
+    diff.removed = old
+        .iter()
+        .filter(|ar| !new_by_gvk.contains_key(&gvk(ar)))
+        .cloned()
+        .collect();
+    diff
 }
 
 // Simple pluralizer. Handles the special cases.
-fn to_plural(word: &str) -> String {
+pub(crate) fn to_plural(word: &str) -> String {
     if word == "endpoints" || word == "endpointslices" {
         return word.to_owned();
     } else if word == "nodemetrics" {
@@ -145,6 +483,276 @@ fn to_plural(word: &str) -> String {
     format!("{word}s")
 }
 
+#[test]
+fn test_discovery_url() {
+    let pod = ApiResource::from_gvk(&GroupVersionKind::gvk("", "v1", "Pod"));
+    assert_eq!(pod.discovery_url(), "/api/v1");
+    let deploy = ApiResource::from_gvk(&GroupVersionKind::gvk("apps", "v1", "Deployment"));
+    assert_eq!(deploy.discovery_url(), "/apis/apps/v1");
+}
+
+#[test]
+fn test_collection_and_item_urls() {
+    let deploy = ApiResource::from_gvk(&GroupVersionKind::gvk("apps", "v1", "Deployment"));
+    assert_eq!(
+        deploy.collection_url(Some("ns")),
+        "/apis/apps/v1/namespaces/ns/deployments"
+    );
+    assert_eq!(
+        deploy.item_url(Some("ns"), "web"),
+        "/apis/apps/v1/namespaces/ns/deployments/web"
+    );
+    let node = ApiResource::from_gvk(&GroupVersionKind::gvk("", "v1", "Node"));
+    assert_eq!(node.collection_url(None), "/api/v1/nodes");
+    assert_eq!(node.item_url(None, "node-1"), "/api/v1/nodes/node-1");
+    assert_eq!(
+        crate::dynamic::DynamicObject::url_path(&deploy, Some("ns")),
+        deploy.collection_url(Some("ns"))
+    );
+}
+
+#[test]
+fn test_scale_patch_request() {
+    let deploy = ApiResource::from_gvk(&GroupVersionKind::gvk("apps", "v1", "Deployment"));
+    let req = deploy.scale_patch_request(Some("ns"), "web", 3).unwrap();
+    assert_eq!(req.method(), http::Method::PATCH);
+    assert_eq!(
+        req.uri().path(),
+        "/apis/apps/v1/namespaces/ns/deployments/web/scale"
+    );
+    assert_eq!(
+        req.headers().get(http::header::CONTENT_TYPE).unwrap(),
+        "application/merge-patch+json"
+    );
+    let body: serde_json::Value = serde_json::from_slice(req.body()).unwrap();
+    assert_eq!(body, serde_json::json!({ "spec": { "replicas": 3 } }));
+}
+
+#[test]
+fn test_from_gvk_matches_from_apiresource() {
+    let discovered = |name: &str, kind: &str| APIResource {
+        name: name.into(),
+        kind: kind.into(),
+        namespaced: true,
+        verbs: vec![verbs::GET.into()],
+        ..APIResource::default()
+    };
+    for (group_version, name, kind) in [
+        ("apps/v1", "deployments", "Deployment"),
+        ("v1", "pods", "Pod"),
+        ("networking.k8s.io/v1", "ingresses", "Ingress"),
+    ] {
+        let gvk = group_version.parse::<GroupVersion>().unwrap().with_kind(kind);
+        let guessed = ApiResource::from_gvk(&gvk);
+        let found = ApiResource::from_apiresource(&discovered(name, kind), group_version).unwrap();
+        assert_eq!(guessed.group, found.group);
+        assert_eq!(guessed.version, found.version);
+        assert_eq!(guessed.api_version, found.api_version);
+        assert_eq!(guessed.kind, found.kind);
+        assert_eq!(found.plural, name);
+    }
+    assert!(ApiResource::from_apiresource(&discovered("pods", "Pod"), "a/b/c").is_err());
+}
+
+#[test]
+fn test_from_gvk_with_custom_pluralizer() {
+    let gvk = GroupVersionKind::gvk("example.com", "v1", "Octopus");
+    assert_eq!(ApiResource::from_gvk(&gvk).plural, "octopuses");
+    let ar = ApiResource::from_gvk_with(&gvk, |kind| {
+        assert_eq!(kind, "octopus");
+        "octopodes".to_string()
+    });
+    assert_eq!(ar.plural, "octopodes");
+    assert_eq!(ar.kind, "Octopus");
+    assert_eq!(ar.api_version, "example.com/v1");
+}
+
+#[test]
+fn test_from_gvk_with_scope() {
+    let gvk = GroupVersionKind::gvk("clux.dev", "v1", "Foo");
+    let (ar, caps) = ApiResource::from_gvk_with_scope(&gvk, Scope::Namespaced);
+    assert_eq!(ar, ApiResource::from_gvk(&gvk));
+    assert_eq!(caps.scope, Scope::Namespaced);
+    assert!(caps.operations.is_empty());
+}
+
+#[test]
+fn test_scope_predicates() {
+    let gvk = GroupVersionKind::gvk("", "v1", "Pod");
+    let (_, pod) = ApiResource::from_gvk_with_scope(&gvk, Scope::Namespaced);
+    assert!(pod.is_namespaced());
+    assert!(!pod.is_cluster_scoped());
+    let gvk = GroupVersionKind::gvk("", "v1", "Node");
+    let (_, node) = ApiResource::from_gvk_with_scope(&gvk, Scope::Cluster);
+    assert!(node.is_cluster_scoped());
+    assert!(!node.is_namespaced());
+}
+
+#[cfg(test)]
+fn namespaced_caps(
+    operations: &[&str],
+    subresources: Vec<(ApiResource, ApiCapabilities)>,
+) -> ApiCapabilities {
+    ApiCapabilities {
+        scope: Scope::Namespaced,
+        subresources,
+        operations: operations.iter().map(|v| v.to_string()).collect(),
+        warning: None,
+    }
+}
+
+#[test]
+fn test_partition_by_scope() {
+    let pod = ApiResource::from_gvk_with_scope(&GroupVersionKind::gvk("", "v1", "Pod"), Scope::Namespaced);
+    let node = ApiResource::from_gvk_with_scope(&GroupVersionKind::gvk("", "v1", "Node"), Scope::Cluster);
+    let deploy = ApiResource::from_gvk_with_scope(
+        &GroupVersionKind::gvk("apps", "v1", "Deployment"),
+        Scope::Namespaced,
+    );
+    let resources = vec![pod.clone(), node.clone(), deploy.clone()];
+
+    let (namespaced, cluster) = partition_by_scope(&resources);
+    assert_eq!(namespaced, vec![pod.0, deploy.0]);
+    assert_eq!(cluster, vec![node.0]);
+}
+
+#[test]
+fn test_diff_discovery() {
+    let pod = ApiResource::from_gvk(&GroupVersionKind::gvk("", "v1", "Pod"));
+    let node = ApiResource::from_gvk(&GroupVersionKind::gvk("", "v1", "Node"));
+    let foo_gvk = GroupVersionKind::gvk("clux.dev", "v1", "Foo");
+    let foo = ApiResource::from_gvk(&foo_gvk);
+    let foo_renamed = ApiResource::from_gvk_with_plural(&foo_gvk, "fooz");
+    let deploy = ApiResource::from_gvk(&GroupVersionKind::gvk("apps", "v1", "Deployment"));
+
+    let old = vec![pod.clone(), node.clone(), foo.clone()];
+    let new = vec![pod.clone(), foo_renamed.clone(), deploy.clone()];
+    let diff = diff_discovery(&old, &new);
+    assert_eq!(diff.added, vec![deploy]);
+    assert_eq!(diff.removed, vec![node]);
+    assert_eq!(diff.changed, vec![(foo, foo_renamed)]);
+    assert!(diff_discovery(&old, &old).is_empty());
+}
+
+#[test]
+fn test_operations_union() {
+    let v1 = Operations::from(
+        &[
+            verbs::GET.to_string(),
+            verbs::LIST.to_string(),
+            "proxy".to_string(),
+        ][..],
+    );
+    let v1beta1 = Operations::from(
+        &[
+            verbs::GET.to_string(),
+            verbs::PATCH.to_string(),
+            "bind".to_string(),
+        ][..],
+    );
+    let union = v1.union(&v1beta1);
+    assert!(union.get && union.list && union.patch);
+    assert!(!union.create && !union.watch);
+    assert_eq!(union.other, vec!["proxy", "bind"]);
+    assert_eq!(v1beta1.union(&v1).other, vec!["bind", "proxy"]);
+}
+
+#[test]
+fn test_operations_serialize_canonically() {
+    let a = Operations::from(["get", "proxy", "escalate"].map(String::from).as_slice());
+    let b = Operations::from(["escalate", "get", "proxy"].map(String::from).as_slice());
+    assert_ne!(a.other, b.other);
+    let json = serde_json::to_string(&a).unwrap();
+    assert_eq!(json, serde_json::to_string(&b).unwrap());
+    assert_eq!(
+        json,
+        r#"{"create":false,"get":true,"list":false,"watch":false,"delete":false,"deleteCollection":false,"update":false,"patch":false,"other":["escalate","proxy"]}"#
+    );
+    let parsed: Operations = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.other, ["escalate", "proxy"]);
+}
+
+#[test]
+fn test_effective_operations() {
+    let all = [
+        verbs::CREATE,
+        verbs::GET,
+        verbs::LIST,
+        verbs::WATCH,
+        verbs::DELETE,
+        verbs::DELETE_COLLECTION,
+        verbs::UPDATE,
+        verbs::PATCH,
+    ];
+    let caps = namespaced_caps(&all, vec![]);
+    let allowed = vec![verbs::GET.to_string(), verbs::LIST.to_string()];
+    let ops = Operations::effective(&caps, &allowed);
+    assert_eq!(ops, Operations {
+        get: true,
+        list: true,
+        ..Operations::default()
+    });
+    assert_eq!(
+        Operations::effective(&caps, &["*".to_string()]),
+        caps.typed_operations()
+    );
+}
+
+#[test]
+fn test_addressable_subresources() {
+    let gvk = GroupVersionKind::gvk("apps", "v1", "Deployment");
+    let parent = ApiResource::from_gvk(&gvk);
+    let scale =
+        ApiResource::from_gvk_with_plural(&GroupVersionKind::gvk("autoscaling", "v1", "Scale"), "scale");
+    let scale_caps = namespaced_caps(&[verbs::GET, verbs::PATCH, verbs::UPDATE], vec![]);
+    let caps = namespaced_caps(&[verbs::GET, verbs::LIST], vec![(scale, scale_caps)]);
+
+    let subresources = caps.addressable_subresources(&parent);
+    assert_eq!(subresources.len(), 1);
+    let (path, operations) = &subresources[0];
+    assert_eq!(path, "deployments/scale");
+    assert_eq!(*operations, ["get", "patch", "update"]);
+}
+
+#[test]
+fn test_full_subresource_names() {
+    let sub = |gvk: GroupVersionKind, name: &str| {
+        (
+            ApiResource::from_gvk_with_plural(&gvk, name),
+            namespaced_caps(&[verbs::GET], vec![]),
+        )
+    };
+    let caps = namespaced_caps(&[verbs::GET, verbs::LIST], vec![
+        sub(GroupVersionKind::gvk("autoscaling", "v1", "Scale"), "scale"),
+        sub(GroupVersionKind::gvk("apps", "v1", "Deployment"), "status"),
+    ]);
+    assert_eq!(caps.full_subresource_names("deployments"), [
+        "deployments/scale",
+        "deployments/status"
+    ]);
+}
+
+#[test]
+fn test_supports_connect_subresources() {
+    let pod_options = GroupVersionKind::gvk("", "v1", "PodExecOptions");
+    let connect = |name: &str| {
+        let ar = ApiResource::from_gvk_with_plural(&pod_options, name);
+        (ar, namespaced_caps(&[verbs::CREATE, verbs::GET], vec![]))
+    };
+    let mut pods = namespaced_caps(&[verbs::GET, verbs::LIST], vec![
+        connect("exec"),
+        connect("attach"),
+        connect("portforward"),
+    ]);
+    assert!(pods.supports_exec());
+    assert!(pods.supports_attach());
+    assert!(pods.supports_portforward());
+
+    pods.subresources.retain(|(ar, _)| ar.plural != "exec");
+    assert!(!pods.supports_exec());
+    assert!(pods.supports_attach());
+}
+
 #[test]
 fn test_to_plural_native() {
     // Extracted from `swagger.json`
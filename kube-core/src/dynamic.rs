@@ -3,12 +3,22 @@
 //! For concrete usage see [examples prefixed with dynamic_](https://github.com/kube-rs/kube/tree/main/examples).
 pub use crate::discovery::ApiResource;
 use crate::{
-    metadata::TypeMeta,
+    discovery::to_plural,
+    gvk::GroupVersionKind,
+    metadata::{ListMeta, TypeMeta},
+    object::ObjectList,
     resource::{DynamicResourceScope, Resource},
+    watch::WatchEvent,
 };
 
-use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
-use std::borrow::Cow;
+use k8s_openapi::{
+    api::core::v1::{Affinity, Toleration},
+    apiextensions_apiserver::pkg::apis::apiextensions::v1::{
+        JSONSchemaProps, JSONSchemaPropsOrArray, JSONSchemaPropsOrBool,
+    },
+    apimachinery::pkg::apis::meta::v1::ObjectMeta,
+};
+use std::{borrow::Cow, collections::BTreeMap};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -19,6 +29,50 @@ pub struct ParseDynamicObjectError {
     source: serde_json::Error,
 }
 
+/// Failed to address a field of a `DynamicObject` by JSON pointer
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum PointerError {
+    /// The pointer was neither empty nor started with `/`
+    #[error("invalid JSON pointer {0:?}: must be empty or start with '/'")]
+    InvalidPointer(String),
+
+    /// An array index was not a number or would leave a gap in the array
+    #[error("invalid array index {0:?}")]
+    InvalidIndex(String),
+
+    /// The pointer traversed into a value that is neither an object nor an array
+    #[error("cannot index into a non-container value at {0:?}")]
+    NotAContainer(String),
+}
+
+/// Aggregated container resource requests and limits
+///
+/// Returned by [`DynamicObject::resource_totals`]. CPU is counted in cores and memory in bytes.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ResourceTotals {
+    /// Sum of the cpu requests, in cores
+    pub cpu_request: f64,
+    /// Sum of the cpu limits, in cores
+    pub cpu_limit: f64,
+    /// Sum of the memory requests, in bytes
+    pub mem_request: f64,
+    /// Sum of the memory limits, in bytes
+    pub mem_limit: f64,
+}
+
+/// Pod scheduling constraints embedded in an object
+///
+/// Returned by [`DynamicObject::scheduling`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SchedulingInfo {
+    /// Labels a node must carry for the pod to be scheduled on it
+    pub node_selector: BTreeMap<String, String>,
+    /// Taints the pod tolerates
+    pub tolerations: Vec<Toleration>,
+    /// Node and pod (anti-)affinity rules
+    pub affinity: Option<Affinity>,
+}
+
 /// A dynamic representation of a kubernetes object
 ///
 /// This will work with any non-list type object.
@@ -60,6 +114,44 @@ impl DynamicObject {
         self
     }
 
+    /// Deserialize an item of a raw list's `items`, filling in missing type fields from the ApiResource
+    ///
+    /// Items of a list usually omit their `apiVersion` and `kind`, which are implied by the list.
+    pub fn from_item(value: serde_json::Value, ar: &ApiResource) -> Result<Self, serde_json::Error> {
+        let mut obj: Self = serde_json::from_value(value)?;
+        obj.types.get_or_insert_with(|| TypeMeta {
+            api_version: ar.api_version.clone(),
+            kind: ar.kind.clone(),
+        });
+        Ok(obj)
+    }
+
+    /// Set the `status` of a DynamicObject
+    #[must_use]
+    pub fn with_status(mut self, status: serde_json::Value) -> Self {
+        if !self.data.is_object() {
+            self.data = serde_json::Value::Object(Default::default());
+        }
+        self.data["status"] = status;
+        self
+    }
+
+    /// A copy of this object with only its type fields, metadata and `status`
+    ///
+    /// This is the form to send when replacing the status subresource.
+    #[must_use]
+    pub fn status_only(&self) -> Self {
+        let mut data = serde_json::Map::new();
+        if let Some(status) = self.data.get("status") {
+            data.insert("status".into(), status.clone());
+        }
+        Self {
+            types: self.types.clone(),
+            metadata: self.metadata.clone(),
+            data: serde_json::Value::Object(data),
+        }
+    }
+
     /// Attach a namespace to a DynamicObject
     #[must_use]
     pub fn within(mut self, ns: &str) -> Self {
@@ -67,6 +159,302 @@ impl DynamicObject {
         self
     }
 
+    /// Default the namespace of this object to `default_ns` if it does not set one
+    ///
+    /// Like `kubectl apply -n`, namespaces specified by the object itself are kept.
+    /// Cluster scoped objects should be skipped by the caller, e.g. with [`ApiCapabilities::is_namespaced`].
+    ///
+    /// [`ApiCapabilities::is_namespaced`]: crate::discovery::ApiCapabilities::is_namespaced
+    pub fn ensure_namespace(&mut self, default_ns: &str) {
+        if self.metadata.namespace.is_none() {
+            self.metadata.namespace = Some(default_ns.into());
+        }
+    }
+
+    /// Wrap a set of `DynamicObject`s in an [`ObjectList`] envelope
+    ///
+    /// The list `TypeMeta` is derived from the first item with type information,
+    /// giving it the `apiVersion` of that item and a `kind` suffixed with `List`.
+    /// Lists without any typed items fall back to the generic `v1` `List`.
+    #[must_use]
+    pub fn into_list(
+        items: Vec<DynamicObject>,
+        resource_version: Option<String>,
+    ) -> ObjectList<DynamicObject> {
+        let types = items
+            .iter()
+            .find_map(|obj| obj.types.as_ref())
+            .map(|tm| TypeMeta {
+                api_version: tm.api_version.clone(),
+                kind: format!("{}List", tm.kind),
+            })
+            .unwrap_or_else(|| TypeMeta {
+                api_version: "v1".to_owned(),
+                kind: "List".to_owned(),
+            });
+        ObjectList {
+            types,
+            metadata: ListMeta {
+                resource_version,
+                ..Default::default()
+            },
+            items,
+        }
+    }
+
+    /// Set a nested value in `data` addressed by a JSON pointer (RFC 6901)
+    ///
+    /// The pointer is resolved against the dynamic `data` (e.g. `/spec/replicas`),
+    /// and intermediate objects are created as needed.
+    /// Array segments may address an existing index or append with the next index (or `-`),
+    /// but indexes leaving a gap in the array are rejected.
+    pub fn set_pointer(&mut self, ptr: &str, value: serde_json::Value) -> Result<(), PointerError> {
+        if ptr.is_empty() {
+            self.data = value;
+            return Ok(());
+        }
+        let tokens = ptr
+            .strip_prefix('/')
+            .ok_or_else(|| PointerError::InvalidPointer(ptr.into()))?
+            .split('/')
+            .map(|t| t.replace("~1", "/").replace("~0", "~"))
+            .collect::<Vec<_>>();
+
+        let mut target = &mut self.data;
+        for token in &tokens {
+            if target.is_null() {
+                *target = serde_json::Value::Object(Default::default());
+            }
+            target = match target {
+                serde_json::Value::Object(map) => {
+                    map.entry(token.as_str()).or_insert(serde_json::Value::Null)
+                }
+                serde_json::Value::Array(arr) => {
+                    let idx = array_index(token, arr.len())?;
+                    if idx == arr.len() {
+                        arr.push(serde_json::Value::Null);
+                    }
+                    &mut arr[idx]
+                }
+                _ => return Err(PointerError::NotAContainer(token.clone())),
+            };
+        }
+        *target = value;
+        Ok(())
+    }
+
+    /// Remove the fields at the given JSON pointer `paths`, if present
+    ///
+    /// Unlike [`DynamicObject::set_pointer`], the paths are resolved against the whole serialized object,
+    /// so they can reach into the metadata as well as the data, e.g. `/metadata/managedFields` or `/status`.
+    /// Paths that do not exist are ignored.
+    ///
+    /// Fails for paths targeting the `apiVersion` or `kind` type fields, or when the remaining object
+    /// no longer deserializes, e.g. after removing a field that an `OwnerReference` requires.
+    pub fn without_fields(self, paths: &[&str]) -> Result<Self, serde_json::Error> {
+        if let Some(path) = paths.iter().find(|path| is_type_field(path)) {
+            return Err(serde::de::Error::custom(format!(
+                "cannot remove type field {path}"
+            )));
+        }
+        let mut value = serde_json::to_value(&self)?;
+        for path in paths {
+            remove_pointer(&mut value, path);
+        }
+        serde_json::from_value(value)
+    }
+
+    /// Trigger a rollout by setting the `kubectl.kubernetes.io/restartedAt` pod template annotation to now
+    ///
+    /// This mirrors `kubectl rollout restart`, and creates `spec.template.metadata.annotations` as needed.
+    /// Fails if part of that path exists but is not an object.
+    pub fn touch_restart(&mut self) -> Result<(), PointerError> {
+        let now = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+        self.set_pointer(
+            "/spec/template/metadata/annotations/kubectl.kubernetes.io~1restartedAt",
+            now.into(),
+        )
+    }
+
+    /// Remove fields from `data` that are not described by a structural `schema`
+    ///
+    /// This previews the pruning the apiserver performs for custom resources with structural schemas.
+    /// Fields under a subtree marked `x-kubernetes-preserve-unknown-fields`, or allowed by
+    /// `additionalProperties`, are kept. The `metadata` and type fields are never pruned.
+    pub fn prune(&mut self, schema: &JSONSchemaProps) {
+        prune_value(&mut self.data, schema);
+    }
+
+    /// Whether this object looks like a List rather than a single object
+    ///
+    /// This is the case when its kind ends in `List`, or when it has an `items` array.
+    /// `DynamicObject` is not meant for lists, so this can be used to reject them early.
+    pub fn is_list(&self) -> bool {
+        self.types.as_ref().is_some_and(|t| t.kind.ends_with("List")) || self.data["items"].is_array()
+    }
+
+    /// Sum the cpu and memory requests and limits of all containers embedded in this object
+    ///
+    /// Containers are read from `spec.template.spec.containers` for workloads like Deployments,
+    /// falling back to `spec.containers` for Pods. Values use the Kubernetes quantity notation:
+    /// a number with an optional binary (`Ki`, `Mi`, ..., `Ei`) or decimal (`n`, `u`, `m`, `k`, `M`, ..., `E`)
+    /// suffix, or a decimal exponent like `1e3`. Values that cannot be parsed are skipped.
+    pub fn resource_totals(&self) -> ResourceTotals {
+        let spec = &self.data["spec"];
+        let containers = spec["template"]["spec"]["containers"]
+            .as_array()
+            .or_else(|| spec["containers"].as_array());
+        let mut totals = ResourceTotals::default();
+        for resources in containers.into_iter().flatten().map(|c| &c["resources"]) {
+            let quantity =
+                |kind: &str, name: &str| parse_quantity(&resources[kind][name]).unwrap_or_default();
+            totals.cpu_request += quantity("requests", "cpu");
+            totals.cpu_limit += quantity("limits", "cpu");
+            totals.mem_request += quantity("requests", "memory");
+            totals.mem_limit += quantity("limits", "memory");
+        }
+        totals
+    }
+
+    /// Extract the pod scheduling constraints embedded in this object
+    ///
+    /// Reads `nodeSelector`, `tolerations` and `affinity` from the pod template at `spec.template.spec`
+    /// for workloads like Deployments, falling back to `spec` for Pods.
+    /// Fields that are missing or malformed are left empty.
+    pub fn scheduling(&self) -> SchedulingInfo {
+        let spec = &self.data["spec"];
+        let pod_spec = if spec["template"]["spec"].is_object() {
+            &spec["template"]["spec"]
+        } else {
+            spec
+        };
+        let field = |name: &str| pod_spec.get(name).cloned().unwrap_or_default();
+        SchedulingInfo {
+            node_selector: serde_json::from_value(field("nodeSelector")).unwrap_or_default(),
+            tolerations: serde_json::from_value(field("tolerations")).unwrap_or_default(),
+            affinity: serde_json::from_value(field("affinity")).unwrap_or_default(),
+        }
+    }
+
+    /// Best-effort plural name of this object's kind, for display purposes
+    ///
+    /// This is a **guess** from `types.kind` with the same rules as [`ApiResource::from_gvk`],
+    /// and can be wrong for complex pluralisations. Returns `None` when `types` is absent.
+    pub fn plural_guess(&self) -> Option<String> {
+        let types = self.types.as_ref()?;
+        Some(to_plural(&types.kind.to_ascii_lowercase()))
+    }
+
+    /// Whether the group, version and kind in the `types` of this object is one of `allowlist`
+    ///
+    /// Objects without `types`, or with an unparseable `apiVersion`, are never allowed.
+    pub fn is_allowed(&self, allowlist: &[GroupVersionKind]) -> bool {
+        self.types
+            .as_ref()
+            .and_then(|types| GroupVersionKind::try_from(types).ok())
+            .is_some_and(|gvk| allowlist.contains(&gvk))
+    }
+
+    /// Build a merge patch that only sets the labels and annotations of this object
+    ///
+    /// Returns `{ "metadata": { "labels": ..., "annotations": ... } }`, usable as a
+    /// [`Patch::Merge`](crate::params::Patch::Merge) or [`Patch::Strategic`](crate::params::Patch::Strategic).
+    /// Keys that exist on the server but not on this object are left untouched by such a patch.
+    pub fn metadata_patch(&self) -> serde_json::Value {
+        serde_json::json!({
+            "metadata": {
+                "labels": self.metadata.labels.clone().unwrap_or_default(),
+                "annotations": self.metadata.annotations.clone().unwrap_or_default(),
+            }
+        })
+    }
+
+    /// Clear server-managed metadata so the object can be sent in a create request
+    ///
+    /// Removes `resourceVersion`, `uid`, `creationTimestamp`, `deletionTimestamp`,
+    /// `deletionGracePeriodSeconds`, `generation`, `selfLink` and `managedFields`,
+    /// which the apiserver either rejects or overwrites on create.
+    #[must_use]
+    pub fn for_create(mut self) -> Self {
+        let meta = &mut self.metadata;
+        meta.resource_version = None;
+        meta.uid = None;
+        meta.creation_timestamp = None;
+        meta.deletion_timestamp = None;
+        meta.deletion_grace_period_seconds = None;
+        meta.generation = None;
+        meta.self_link = None;
+        meta.managed_fields = None;
+        self
+    }
+
+    /// Remove empty metadata collections so that they compare equal to absent ones
+    ///
+    /// Clears `labels`, `annotations`, `finalizers`, `ownerReferences` and `managedFields`
+    /// when they are present but empty, as the apiserver treats both forms the same.
+    pub fn normalize_empty_collections(&mut self) {
+        let meta = &mut self.metadata;
+        if meta.labels.as_ref().is_some_and(|m| m.is_empty()) {
+            meta.labels = None;
+        }
+        if meta.annotations.as_ref().is_some_and(|m| m.is_empty()) {
+            meta.annotations = None;
+        }
+        if meta.finalizers.as_ref().is_some_and(|v| v.is_empty()) {
+            meta.finalizers = None;
+        }
+        if meta.owner_references.as_ref().is_some_and(|v| v.is_empty()) {
+            meta.owner_references = None;
+        }
+        if meta.managed_fields.as_ref().is_some_and(|v| v.is_empty()) {
+            meta.managed_fields = None;
+        }
+    }
+
+    /// Serialize this object to JSON with recursively sorted keys, for stable hashing
+    ///
+    /// The volatile `metadata.resourceVersion` and `metadata.managedFields` are left out,
+    /// so logically equal objects produce identical output.
+    pub fn canonical_json(&self) -> String {
+        canonicalize(self.canonical_value()).to_string()
+    }
+
+    // The serialized object without its volatile metadata, before sorting
+    fn canonical_value(&self) -> serde_json::Value {
+        let mut obj = self.clone();
+        obj.metadata.resource_version = None;
+        obj.metadata.managed_fields = None;
+        serde_json::to_value(obj).expect("DynamicObject serializes to json")
+    }
+
+    /// Compare two objects, treating empty and absent metadata collections as equal
+    ///
+    /// See [`DynamicObject::normalize_empty_collections`].
+    #[must_use]
+    pub fn semantically_eq(&self, other: &DynamicObject) -> bool {
+        let mut lhs = self.clone();
+        let mut rhs = other.clone();
+        lhs.normalize_empty_collections();
+        rhs.normalize_empty_collections();
+        lhs == rhs
+    }
+
+    /// Compare the [canonical json](DynamicObject::canonical_json) of two objects, ignoring some fields
+    ///
+    /// Each entry in `ignore_paths` is a JSON pointer (e.g. `/status`) removed from the serialized json
+    /// of both sides before comparing, as in [`DynamicObject::without_fields`].
+    #[must_use]
+    pub fn eq_ignoring(&self, other: &DynamicObject, ignore_paths: &[&str]) -> bool {
+        let stripped = |obj: &DynamicObject| {
+            let mut value = obj.canonical_value();
+            for path in ignore_paths {
+                remove_pointer(&mut value, path);
+            }
+            canonicalize(value)
+        };
+        stripped(self) == stripped(other)
+    }
+
     /// Attempt to convert this `DynamicObject` to a `Resource`
     pub fn try_parse<K: Resource + for<'a> serde::Deserialize<'a>>(
         self,
@@ -75,6 +463,128 @@ impl DynamicObject {
     }
 }
 
+// Rebuild every object in `value` with its keys inserted in sorted order
+fn canonicalize(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut entries = map.into_iter().collect::<Vec<_>>();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            let sorted = entries.into_iter().map(|(k, v)| (k, canonicalize(v))).collect();
+            serde_json::Value::Object(sorted)
+        }
+        serde_json::Value::Array(items) => items.into_iter().map(canonicalize).collect(),
+        other => other,
+    }
+}
+
+// Recursively drop the object fields of `value` that `schema` does not specify
+fn prune_value(value: &mut serde_json::Value, schema: &JSONSchemaProps) {
+    let preserve = schema.x_kubernetes_preserve_unknown_fields == Some(true);
+    match value {
+        serde_json::Value::Object(map) => {
+            map.retain(|key, field| {
+                let field_schema = schema.properties.as_ref().and_then(|props| props.get(key));
+                match (field_schema, &schema.additional_properties) {
+                    (Some(s), _) => {
+                        prune_value(field, s);
+                        true
+                    }
+                    (None, Some(JSONSchemaPropsOrBool::Schema(s))) => {
+                        prune_value(field, s);
+                        true
+                    }
+                    (None, Some(JSONSchemaPropsOrBool::Bool(allowed))) => *allowed || preserve,
+                    (None, None) => preserve,
+                }
+            });
+        }
+        serde_json::Value::Array(items) => match &schema.items {
+            Some(JSONSchemaPropsOrArray::Schema(s)) => items.iter_mut().for_each(|item| prune_value(item, s)),
+            Some(JSONSchemaPropsOrArray::Schemas(schemas)) => items
+                .iter_mut()
+                .zip(schemas)
+                .for_each(|(item, s)| prune_value(item, s)),
+            None => {}
+        },
+        _ => {}
+    }
+}
+
+// Parse a Kubernetes quantity, given either as a string or a plain JSON number
+fn parse_quantity(value: &serde_json::Value) -> Option<f64> {
+    const SUFFIXES: [(&str, f64, i32); 15] = [
+        ("Ki", 1024.0, 1),
+        ("Mi", 1024.0, 2),
+        ("Gi", 1024.0, 3),
+        ("Ti", 1024.0, 4),
+        ("Pi", 1024.0, 5),
+        ("Ei", 1024.0, 6),
+        ("n", 10.0, -9),
+        ("u", 10.0, -6),
+        ("m", 10.0, -3),
+        ("k", 10.0, 3),
+        ("M", 10.0, 6),
+        ("G", 10.0, 9),
+        ("T", 10.0, 12),
+        ("P", 10.0, 15),
+        ("E", 10.0, 18),
+    ];
+    if let Some(n) = value.as_f64() {
+        return Some(n);
+    }
+    let q = value.as_str()?.trim();
+    let (number, base, exp) = SUFFIXES
+        .iter()
+        .find_map(|(suffix, base, exp)| q.strip_suffix(suffix).map(|n| (n, *base, *exp)))
+        .unwrap_or((q, 1.0, 0));
+    let number = number.parse::<f64>().ok().filter(|n| n.is_finite())?;
+    // divide for negative exponents to keep values like 250m exact
+    Some(if exp < 0 {
+        number / base.powi(-exp)
+    } else {
+        number * base.powi(exp)
+    })
+}
+
+// Remove the value at a JSON pointer, ignoring paths that do not exist
+// Whether a JSON pointer targets the flattened `TypeMeta` of an object
+fn is_type_field(ptr: &str) -> bool {
+    matches!(ptr.split('/').nth(1), Some("apiVersion" | "kind"))
+}
+
+fn remove_pointer(value: &mut serde_json::Value, ptr: &str) {
+    let Some((parent, last)) = ptr.rsplit_once('/') else {
+        return;
+    };
+    let last = last.replace("~1", "/").replace("~0", "~");
+    match value.pointer_mut(parent) {
+        Some(serde_json::Value::Object(map)) => {
+            map.remove(&last);
+        }
+        Some(serde_json::Value::Array(arr)) => {
+            if let Some(idx) = last.parse::<usize>().ok().filter(|idx| *idx < arr.len()) {
+                arr.remove(idx);
+            }
+        }
+        _ => {}
+    }
+}
+
+// Resolve a JSON pointer array segment, allowing at most an append at the end
+fn array_index(token: &str, len: usize) -> Result<usize, PointerError> {
+    let idx = if token == "-" {
+        len
+    } else {
+        token
+            .parse::<usize>()
+            .map_err(|_| PointerError::InvalidIndex(token.into()))?
+    };
+    if idx > len {
+        return Err(PointerError::InvalidIndex(token.into()));
+    }
+    Ok(idx)
+}
+
 impl Resource for DynamicObject {
     type DynamicType = ApiResource;
     type Scope = DynamicResourceScope;
@@ -99,6 +609,10 @@ impl Resource for DynamicObject {
         dt.plural.as_str().into()
     }
 
+    fn url_path(dt: &ApiResource, namespace: Option<&str>) -> String {
+        dt.collection_url(namespace)
+    }
+
     fn meta(&self) -> &ObjectMeta {
         &self.metadata
     }
@@ -108,16 +622,33 @@ impl Resource for DynamicObject {
     }
 }
 
+/// Extract the `resourceVersion` carried by a [`WatchEvent::Bookmark`] on a dynamic watch
+///
+/// Returns `None` for all other events, or when the bookmark has an empty resource version.
+/// The returned version can be used to resume a watch after a disconnect.
+pub fn bookmark_resource_version(event: &WatchEvent<DynamicObject>) -> Option<String> {
+    match event {
+        WatchEvent::Bookmark(bm) if !bm.metadata.resource_version.is_empty() => {
+            Some(bm.metadata.resource_version.clone())
+        }
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod test {
+    use super::bookmark_resource_version;
     use crate::{
-        dynamic::{ApiResource, DynamicObject},
+        dynamic::{ApiResource, DynamicObject, PointerError},
         gvk::GroupVersionKind,
-        params::{Patch, PatchParams, PostParams},
+        params::{DeleteParams, Patch, PatchParams, PostParams, Preconditions},
         request::Request,
         resource::Resource,
+        watch::WatchEvent,
+    };
+    use k8s_openapi::{
+        api::core::v1::Pod, apiextensions_apiserver::pkg::apis::apiextensions::v1::JSONSchemaProps,
     };
-    use k8s_openapi::api::core::v1::Pod;
 
     #[test]
     fn raw_custom_resource() {
@@ -136,6 +667,99 @@ mod test {
         assert_eq!(req.method(), "PATCH");
     }
 
+    #[test]
+    fn raw_custom_resource_apply() {
+        let gvk = GroupVersionKind::gvk("clux.dev", "v1", "Foo");
+        let res = ApiResource::from_gvk(&gvk);
+        let url = DynamicObject::url_path(&res, Some("myns"));
+
+        let patch = serde_json::json!({ "spec": { "replicas": 2 } });
+        let pp = PatchParams::apply("ctrl").force();
+        let req = Request::new(url)
+            .patch("baz", &pp, &Patch::Apply(&patch))
+            .unwrap();
+        assert_eq!(req.method(), "PATCH");
+        assert_eq!(req.uri().path(), "/apis/clux.dev/v1/namespaces/myns/foos/baz");
+        let query = req.uri().query().unwrap();
+        assert!(query.contains("fieldManager=ctrl"));
+        assert!(query.contains("force=true"));
+        assert_eq!(
+            req.headers().get(http::header::CONTENT_TYPE).unwrap(),
+            "application/apply-patch+yaml"
+        );
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(req.body()).unwrap(),
+            patch
+        );
+    }
+
+    #[test]
+    fn raw_custom_resource_force_requires_apply() {
+        let gvk = GroupVersionKind::gvk("clux.dev", "v1", "Foo");
+        let res = ApiResource::from_gvk(&gvk);
+        let url = DynamicObject::url_path(&res, Some("myns"));
+
+        let pp = PatchParams::apply("ctrl").force();
+        let err = Request::new(url)
+            .patch("baz", &pp, &Patch::Merge(()))
+            .unwrap_err();
+        assert!(err.to_string().contains("force only works with Patch::Apply"));
+    }
+
+    #[test]
+    fn raw_custom_resource_dry_run() {
+        let res = ApiResource::from_gvk(&GroupVersionKind::gvk("clux.dev", "v1", "Foo"));
+        let url = DynamicObject::url_path(&res, Some("myns"));
+
+        let pp = PostParams {
+            dry_run: true,
+            ..Default::default()
+        };
+        let req = Request::new(&url).create(&pp, vec![]).unwrap();
+        assert_eq!(req.uri(), "/apis/clux.dev/v1/namespaces/myns/foos?&dryRun=All");
+
+        let patch_params = PatchParams::default().dry_run();
+        let req = Request::new(url)
+            .patch("baz", &patch_params, &Patch::Merge(()))
+            .unwrap();
+        assert_eq!(
+            req.uri(),
+            "/apis/clux.dev/v1/namespaces/myns/foos/baz?&dryRun=All"
+        );
+    }
+
+    #[test]
+    fn raw_custom_resource_graceful_delete() {
+        let gvk = GroupVersionKind::gvk("clux.dev", "v1", "Foo");
+        let res = ApiResource::from_gvk(&gvk);
+        let url = DynamicObject::url_path(&res, Some("myns"));
+        let dp = DeleteParams::foreground()
+            .grace_period(30)
+            .preconditions(Preconditions {
+                uid: Some("4f6d2b1a".into()),
+                resource_version: Some("100".into()),
+            });
+        let req = Request::new(url).delete("baz", &dp).unwrap();
+        assert_eq!(req.method(), http::Method::DELETE);
+        assert_eq!(req.uri(), "/apis/clux.dev/v1/namespaces/myns/foos/baz?");
+        let body: serde_json::Value = serde_json::from_slice(req.body()).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "gracePeriodSeconds": 30,
+                "propagationPolicy": "Foreground",
+                "preconditions": { "uid": "4f6d2b1a", "resourceVersion": "100" },
+            })
+        );
+
+        let req = Request::new(DynamicObject::url_path(&res, None))
+            .delete_collection(&DeleteParams::background().grace_period(0), &Default::default())
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(req.body()).unwrap();
+        assert_eq!(body["propagationPolicy"], "Background");
+        assert_eq!(body["gracePeriodSeconds"], 0);
+    }
+
     #[test]
     fn raw_resource_in_default_group() {
         let gvk = GroupVersionKind::gvk("", "v1", "Service");
@@ -146,6 +770,428 @@ mod test {
         assert_eq!(req.uri(), "/api/v1/services?");
     }
 
+    #[test]
+    fn dynamic_objects_into_list() {
+        let gvk = GroupVersionKind::gvk("clux.dev", "v1", "Foo");
+        let res = ApiResource::from_gvk(&gvk);
+        let items = vec![DynamicObject::new("a", &res), DynamicObject::new("b", &res)];
+        let list = DynamicObject::into_list(items, Some("1234".into()));
+        assert_eq!(list.types.api_version, "clux.dev/v1");
+        assert_eq!(list.types.kind, "FooList");
+        assert_eq!(list.metadata.resource_version.as_deref(), Some("1234"));
+        assert_eq!(list.items.len(), 2);
+
+        let empty = DynamicObject::into_list(vec![], None);
+        assert_eq!(empty.types.api_version, "v1");
+        assert_eq!(empty.types.kind, "List");
+        assert!(empty.metadata.resource_version.is_none());
+        assert!(empty.items.is_empty());
+    }
+
+    #[test]
+    fn ensure_namespace_keeps_explicit_namespace() {
+        let ar = ApiResource::from_gvk(&GroupVersionKind::gvk("apps", "v1", "Deployment"));
+        let mut missing = DynamicObject::new("a", &ar);
+        missing.ensure_namespace("target");
+        assert_eq!(missing.metadata.namespace.as_deref(), Some("target"));
+
+        let mut explicit = DynamicObject::new("b", &ar).within("other");
+        explicit.ensure_namespace("target");
+        assert_eq!(explicit.metadata.namespace.as_deref(), Some("other"));
+    }
+
+    #[test]
+    fn set_pointer_on_existing_path() {
+        let gvk = GroupVersionKind::gvk("apps", "v1", "Deployment");
+        let res = ApiResource::from_gvk(&gvk);
+        let mut obj = DynamicObject::new("web", &res).data(serde_json::json!({
+            "spec": { "replicas": 1, "template": { "spec": { "containers": [{ "image": "nginx:1" }] } } }
+        }));
+        obj.set_pointer("/spec/replicas", 3.into()).unwrap();
+        obj.set_pointer("/spec/template/spec/containers/0/image", "nginx:2".into())
+            .unwrap();
+        assert_eq!(obj.data["spec"]["replicas"], 3);
+        assert_eq!(
+            obj.data["spec"]["template"]["spec"]["containers"][0]["image"],
+            "nginx:2"
+        );
+    }
+
+    #[test]
+    fn set_pointer_creates_nested_path() {
+        let gvk = GroupVersionKind::gvk("clux.dev", "v1", "Foo");
+        let res = ApiResource::from_gvk(&gvk);
+        let mut obj = DynamicObject::new("foo", &res);
+        obj.set_pointer("/spec/config/a~1b", serde_json::json!({"enabled": true}))
+            .unwrap();
+        assert_eq!(
+            obj.data,
+            serde_json::json!({ "spec": { "config": { "a/b": { "enabled": true } } } })
+        );
+
+        obj.set_pointer("/spec/items/0", "first".into()).unwrap();
+        obj.set_pointer("/spec/items/-", "second".into()).unwrap();
+        assert_eq!(obj.data["spec"]["items"], serde_json::json!(["first", "second"]));
+    }
+
+    #[test]
+    fn set_pointer_rejects_invalid_paths() {
+        let gvk = GroupVersionKind::gvk("clux.dev", "v1", "Foo");
+        let res = ApiResource::from_gvk(&gvk);
+        let mut obj = DynamicObject::new("foo", &res).data(serde_json::json!({
+            "spec": { "items": ["a"], "name": "foo" }
+        }));
+        assert_eq!(
+            obj.set_pointer("/spec/items/3", "gap".into()),
+            Err(PointerError::InvalidIndex("3".into()))
+        );
+        assert_eq!(
+            obj.set_pointer("/spec/name/first", "x".into()),
+            Err(PointerError::NotAContainer("first".into()))
+        );
+        assert_eq!(
+            obj.set_pointer("spec", "x".into()),
+            Err(PointerError::InvalidPointer("spec".into()))
+        );
+        assert_eq!(obj.data["spec"]["items"], serde_json::json!(["a"]));
+    }
+
+    #[test]
+    fn without_fields_strips_metadata_and_data() {
+        let res = ApiResource::from_gvk(&GroupVersionKind::gvk("apps", "v1", "Deployment"));
+        let mut obj = DynamicObject::new("web", &res)
+            .data(serde_json::json!({ "spec": { "replicas": 1 } }))
+            .with_status(serde_json::json!({ "readyReplicas": 1 }));
+        obj.metadata.managed_fields = Some(vec![Default::default()]);
+        obj.metadata.annotations = Some([("a/b".to_string(), "c".to_string())].into());
+
+        let stripped = obj
+            .clone()
+            .without_fields(&[
+                "/status",
+                "/metadata/managedFields",
+                "/metadata/annotations/a~1b",
+                "/spec/missing/field",
+            ])
+            .unwrap();
+        assert_eq!(stripped.metadata.managed_fields, None);
+        assert_eq!(stripped.metadata.annotations, Some(Default::default()));
+        assert_eq!(stripped.data, serde_json::json!({ "spec": { "replicas": 1 } }));
+        assert_eq!(stripped.types, obj.types);
+
+        assert_eq!(
+            obj.clone().without_fields(&["/nonexistent", "spec"]).unwrap(),
+            obj
+        );
+
+        assert!(obj.clone().without_fields(&["/apiVersion"]).is_err());
+        assert!(obj.clone().without_fields(&["/kind"]).is_err());
+        obj.metadata.owner_references = Some(vec![Default::default()]);
+        assert!(obj.without_fields(&["/metadata/ownerReferences/0/uid"]).is_err());
+    }
+
+    #[test]
+    fn from_item_fills_missing_types() {
+        let res = ApiResource::from_gvk(&GroupVersionKind::gvk("apps", "v1", "Deployment"));
+        let item = serde_json::json!({ "metadata": { "name": "web" }, "spec": { "replicas": 1 } });
+        let obj = DynamicObject::from_item(item, &res).unwrap();
+        let types = obj.types.unwrap();
+        assert_eq!(types.api_version, "apps/v1");
+        assert_eq!(types.kind, "Deployment");
+        assert_eq!(obj.metadata.name.as_deref(), Some("web"));
+        assert_eq!(obj.data["spec"]["replicas"], 1);
+
+        let typed = serde_json::json!({ "apiVersion": "apps/v1beta1", "kind": "Deployment", "metadata": {} });
+        let obj = DynamicObject::from_item(typed, &res).unwrap();
+        assert_eq!(obj.types.unwrap().api_version, "apps/v1beta1");
+    }
+
+    #[test]
+    fn status_only_strips_everything_but_status() {
+        let res = ApiResource::from_gvk(&GroupVersionKind::gvk("clux.dev", "v1", "Foo"));
+        let obj = DynamicObject::new("foo", &res)
+            .within("ns")
+            .data(serde_json::json!({ "spec": { "replicas": 2 } }))
+            .with_status(serde_json::json!({ "ready": true }));
+        assert_eq!(obj.data["spec"]["replicas"], 2);
+        assert_eq!(obj.data["status"]["ready"], true);
+
+        let status = serde_json::to_value(obj.status_only()).unwrap();
+        assert_eq!(
+            status,
+            serde_json::json!({
+                "apiVersion": "clux.dev/v1",
+                "kind": "Foo",
+                "metadata": { "name": "foo", "namespace": "ns" },
+                "status": { "ready": true },
+            })
+        );
+        let bare = DynamicObject::new("foo", &res).with_status(serde_json::json!({}));
+        assert_eq!(bare.data, serde_json::json!({ "status": {} }));
+    }
+
+    #[test]
+    fn canonical_json_is_order_independent() {
+        let a: DynamicObject = serde_json::from_str(
+            r#"{"apiVersion":"v1","kind":"ConfigMap","metadata":{"name":"cm","resourceVersion":"1","labels":{"b":"2","a":"1"}},"data":{"y":"1","x":{"d":1,"c":[{"f":1,"e":2}]}}}"#,
+        )
+        .unwrap();
+        let b: DynamicObject = serde_json::from_str(
+            r#"{"data":{"x":{"c":[{"e":2,"f":1}],"d":1},"y":"1"},"metadata":{"labels":{"a":"1","b":"2"},"resourceVersion":"2","name":"cm"},"kind":"ConfigMap","apiVersion":"v1"}"#,
+        )
+        .unwrap();
+        assert_eq!(a.canonical_json(), b.canonical_json());
+        assert_eq!(
+            a.canonical_json(),
+            r#"{"apiVersion":"v1","data":{"x":{"c":[{"e":2,"f":1}],"d":1},"y":"1"},"kind":"ConfigMap","metadata":{"labels":{"a":"1","b":"2"},"name":"cm"}}"#
+        );
+    }
+
+    #[test]
+    fn touch_restart_sets_template_annotation() {
+        let res = ApiResource::from_gvk(&GroupVersionKind::gvk("apps", "v1", "Deployment"));
+        let mut obj = DynamicObject::new("web", &res).data(serde_json::json!({
+            "spec": { "template": { "spec": { "containers": [] } } }
+        }));
+        obj.touch_restart().unwrap();
+        let restarted = obj.data["spec"]["template"]["metadata"]["annotations"]
+            ["kubectl.kubernetes.io/restartedAt"]
+            .as_str()
+            .unwrap();
+        assert!(chrono::DateTime::parse_from_rfc3339(restarted).is_ok());
+        assert_eq!(
+            obj.data["spec"]["template"]["spec"]["containers"],
+            serde_json::json!([])
+        );
+    }
+
+    #[test]
+    fn prune_against_structural_schema() {
+        let schema: JSONSchemaProps = serde_json::from_value(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "spec": {
+                    "type": "object",
+                    "properties": {
+                        "replicas": { "type": "integer" },
+                        "config": { "type": "object", "x-kubernetes-preserve-unknown-fields": true },
+                        "ports": {
+                            "type": "array",
+                            "items": { "type": "object", "properties": { "port": { "type": "integer" } } }
+                        },
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let res = ApiResource::from_gvk(&GroupVersionKind::gvk("clux.dev", "v1", "Foo"));
+        let mut obj = DynamicObject::new("foo", &res).data(serde_json::json!({
+            "spec": {
+                "replicas": 2,
+                "typo": true,
+                "config": { "anything": { "nested": [1, 2] } },
+                "ports": [{ "port": 80, "name": "http" }],
+            },
+            "extra": "dropped",
+        }));
+        obj.prune(&schema);
+        assert_eq!(
+            obj.data,
+            serde_json::json!({
+                "spec": {
+                    "replicas": 2,
+                    "config": { "anything": { "nested": [1, 2] } },
+                    "ports": [{ "port": 80 }],
+                }
+            })
+        );
+        assert_eq!(obj.metadata.name.as_deref(), Some("foo"));
+    }
+
+    #[test]
+    fn resource_totals_across_containers() {
+        let res = ApiResource::from_gvk(&GroupVersionKind::gvk("apps", "v1", "Deployment"));
+        let deploy = DynamicObject::new("web", &res).data(serde_json::json!({
+            "spec": {
+                "template": {
+                    "spec": {
+                        "containers": [{
+                            "name": "app",
+                            "resources": {
+                                "requests": { "cpu": "250m", "memory": "64Mi" },
+                                "limits": { "cpu": "500m", "memory": "128Mi" },
+                            },
+                        }, {
+                            "name": "sidecar",
+                            "resources": {
+                                "requests": { "cpu": 1, "memory": "1G" },
+                            },
+                        }],
+                    }
+                }
+            }
+        }));
+        let totals = deploy.resource_totals();
+        assert_eq!(totals.cpu_request, 1.25);
+        assert_eq!(totals.cpu_limit, 0.5);
+        assert_eq!(totals.mem_request, (64 * 1024 * 1024 + 1_000_000_000) as f64);
+        assert_eq!(totals.mem_limit, (128 * 1024 * 1024) as f64);
+
+        let pod = DynamicObject::new("web", &res).data(serde_json::json!({
+            "spec": { "containers": [{ "resources": { "limits": { "memory": "1e3" } } }] }
+        }));
+        assert_eq!(pod.resource_totals().mem_limit, 1000.0);
+    }
+
+    #[test]
+    fn scheduling_from_pod_template() {
+        let ar = ApiResource::from_gvk(&GroupVersionKind::gvk("apps", "v1", "Deployment"));
+        let deploy = DynamicObject::new("web", &ar).data(serde_json::json!({
+            "spec": {
+                "template": {
+                    "spec": {
+                        "nodeSelector": { "disktype": "ssd" },
+                        "tolerations": [{ "key": "gpu", "operator": "Exists", "effect": "NoSchedule" }],
+                        "containers": [{ "name": "web", "image": "nginx" }],
+                    }
+                }
+            }
+        }));
+        let scheduling = deploy.scheduling();
+        assert_eq!(scheduling.node_selector["disktype"], "ssd");
+        assert_eq!(scheduling.tolerations.len(), 1);
+        assert_eq!(scheduling.tolerations[0].key.as_deref(), Some("gpu"));
+        assert_eq!(scheduling.tolerations[0].effect.as_deref(), Some("NoSchedule"));
+        assert_eq!(scheduling.affinity, None);
+
+        let pod_ar = ApiResource::from_gvk(&GroupVersionKind::gvk("", "v1", "Pod"));
+        let pod = DynamicObject::new("p", &pod_ar).data(serde_json::json!({
+            "spec": { "nodeSelector": { "zone": "a" } }
+        }));
+        assert_eq!(pod.scheduling().node_selector["zone"], "a");
+        assert_eq!(DynamicObject::new("p", &pod_ar).scheduling(), Default::default());
+    }
+
+    #[test]
+    fn metadata_patch_only_has_labels_and_annotations() {
+        let ar = ApiResource::from_gvk(&GroupVersionKind::gvk("apps", "v1", "Deployment"));
+        let mut obj = DynamicObject::new("web", &ar)
+            .within("ns")
+            .data(serde_json::json!({ "spec": { "replicas": 2 } }));
+        obj.metadata.labels = Some([("app".to_string(), "web".to_string())].into());
+        obj.metadata.annotations = Some([("team".to_string(), "infra".to_string())].into());
+        obj.metadata.resource_version = Some("42".into());
+
+        assert_eq!(
+            obj.metadata_patch(),
+            serde_json::json!({
+                "metadata": {
+                    "labels": { "app": "web" },
+                    "annotations": { "team": "infra" },
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn detects_list_objects() {
+        let list: DynamicObject = serde_json::from_value(serde_json::json!({
+            "apiVersion": "v1",
+            "kind": "PodList",
+            "metadata": {},
+            "items": [],
+        }))
+        .unwrap();
+        assert!(list.is_list());
+
+        let untyped: DynamicObject = serde_json::from_value(serde_json::json!({
+            "metadata": {},
+            "items": [{ "metadata": { "name": "foo" } }],
+        }))
+        .unwrap();
+        assert!(untyped.is_list());
+
+        let res = ApiResource::from_gvk(&GroupVersionKind::gvk("", "v1", "Pod"));
+        let pod = DynamicObject::new("foo", &res).data(serde_json::json!({ "spec": { "items": 1 } }));
+        assert!(!pod.is_list());
+    }
+
+    #[test]
+    fn plural_guess_from_types() {
+        let res = ApiResource::from_gvk(&GroupVersionKind::gvk("apps", "v1", "Deployment"));
+        let mut obj = DynamicObject::new("foo", &res);
+        assert_eq!(obj.plural_guess().as_deref(), Some("deployments"));
+        obj.types = None;
+        assert_eq!(obj.plural_guess(), None);
+    }
+
+    #[test]
+    fn is_allowed_by_gvk_allowlist() {
+        let allowlist = [
+            GroupVersionKind::gvk("apps", "v1", "Deployment"),
+            GroupVersionKind::gvk("", "v1", "ConfigMap"),
+        ];
+        let deploy = ApiResource::from_gvk(&allowlist[0]);
+        let mut obj = DynamicObject::new("foo", &deploy);
+        assert!(obj.is_allowed(&allowlist));
+
+        let secret = ApiResource::from_gvk(&GroupVersionKind::gvk("", "v1", "Secret"));
+        assert!(!DynamicObject::new("bar", &secret).is_allowed(&allowlist));
+
+        obj.types = None;
+        assert!(!obj.is_allowed(&allowlist));
+    }
+
+    #[test]
+    fn empty_labels_are_semantically_absent() {
+        let res = ApiResource::from_gvk(&GroupVersionKind::gvk("clux.dev", "v1", "Foo"));
+        let bare = DynamicObject::new("foo", &res);
+        let mut empty = bare.clone();
+        empty.metadata.labels = Some(Default::default());
+        empty.metadata.finalizers = Some(vec![]);
+        assert_ne!(bare, empty);
+        assert!(bare.semantically_eq(&empty));
+        assert!(empty.semantically_eq(&bare));
+
+        empty.normalize_empty_collections();
+        assert_eq!(bare, empty);
+
+        let mut labelled = bare.clone();
+        labelled.metadata.labels = Some([("app".to_string(), "foo".to_string())].into());
+        assert!(!labelled.semantically_eq(&bare));
+    }
+
+    #[test]
+    fn eq_ignoring_paths() {
+        let ar = ApiResource::from_gvk(&GroupVersionKind::gvk("clux.dev", "v1", "Foo"));
+        let a = DynamicObject::new("foo", &ar).data(serde_json::json!({
+            "spec": { "replicas": 1 },
+            "status": { "ready": false },
+        }));
+        let b = DynamicObject::new("foo", &ar).data(serde_json::json!({
+            "spec": { "replicas": 1 },
+            "status": { "ready": true },
+        }));
+        assert!(a.eq_ignoring(&b, &["/status"]));
+        assert!(!a.eq_ignoring(&b, &[]));
+        assert!(!a.eq_ignoring(&b, &["/spec"]));
+    }
+
+    #[test]
+    fn bookmark_resource_version_from_dynamic_watch() {
+        let bookmark: WatchEvent<DynamicObject> = serde_json::from_str(
+            r#"{"type":"BOOKMARK","object":{"apiVersion":"clux.dev/v1","kind":"Foo","metadata":{"resourceVersion":"12345"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(bookmark_resource_version(&bookmark).as_deref(), Some("12345"));
+
+        let added: WatchEvent<DynamicObject> = serde_json::from_str(
+            r#"{"type":"ADDED","object":{"apiVersion":"clux.dev/v1","kind":"Foo","metadata":{"name":"a","resourceVersion":"1"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(bookmark_resource_version(&added), None);
+    }
+
     #[test]
     fn can_parse_dynamic_object_into_pod() -> Result<(), serde_json::Error> {
         let original_pod: Pod = serde_json::from_value(serde_json::json!({
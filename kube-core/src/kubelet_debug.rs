@@ -45,7 +45,7 @@ impl Request {
         ap.append_to_url_serializer_local(&mut qp);
 
         let req = http::Request::get(qp.finish());
-        req.body(vec![]).map_err(Error::BuildRequest)
+        self.build(req, vec![])
     }
 
     /// Execute a command in a pod directly from the node
@@ -70,7 +70,7 @@ impl Request {
         }
 
         let req = http::Request::get(qp.finish());
-        req.body(vec![]).map_err(Error::BuildRequest)
+        self.build(req, vec![])
     }
 
     /// Forward ports of a pod directly from the node
@@ -106,7 +106,7 @@ impl Request {
             &ports.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(","),
         );
         let req = http::Request::get(qp.finish());
-        req.body(vec![]).map_err(Error::BuildRequest)
+        self.build(req, vec![])
     }
 
     /// Stream logs directly from node
@@ -156,7 +156,7 @@ impl Request {
 
         let urlstr = qp.finish();
         let req = http::Request::get(urlstr);
-        req.body(vec![]).map_err(Error::BuildRequest)
+        self.build(req, vec![])
     }
 }
 
@@ -1,7 +1,7 @@
 //! Request builder type for arbitrary api types
 use thiserror::Error;
 
-use crate::params::GetParams;
+use crate::{dynamic::DynamicObject, params::GetParams};
 
 use super::params::{DeleteParams, ListParams, Patch, PatchParams, PostParams, WatchParams};
 
@@ -40,6 +40,7 @@ pub enum Error {
 pub struct Request {
     /// The path component of a url
     pub url_path: String,
+    trace_context: Option<TraceContext>,
 }
 
 impl Request {
@@ -47,8 +48,100 @@ impl Request {
     pub fn new<S: Into<String>>(url_path: S) -> Self {
         Self {
             url_path: url_path.into(),
+            trace_context: None,
         }
     }
+
+    /// Propagate a trace context on every request built from this one
+    #[must_use]
+    pub fn with_trace_context(mut self, trace_context: TraceContext) -> Self {
+        self.trace_context = Some(trace_context);
+        self
+    }
+
+    // Finish a request from a builder method, attaching the trace context if any
+    pub(crate) fn build(
+        &self,
+        req: http::request::Builder,
+        body: Vec<u8>,
+    ) -> Result<http::Request<Vec<u8>>, Error> {
+        let mut req = req.body(body).map_err(Error::BuildRequest)?;
+        if let Some(trace_context) = &self.trace_context {
+            trace_context.apply(&mut req);
+        }
+        Ok(req)
+    }
+}
+
+/// W3C trace context headers to propagate onto requests
+///
+/// Built from the `traceparent` (and optionally `tracestate`) of the current span,
+/// and set on every request built by a [`Request`] configured with [`Request::with_trace_context`],
+/// including the ones upgraded to WebSockets.
+///
+/// ```
+/// use kube_core::request::{Request, TraceContext};
+/// let ctx = TraceContext::new("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01").unwrap();
+/// let req = Request::new("/api/v1/namespaces/ns/pods")
+///     .with_trace_context(ctx)
+///     .get("foo", &Default::default())
+///     .unwrap();
+/// assert!(req.headers().contains_key("traceparent"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceContext {
+    traceparent: http::HeaderValue,
+    tracestate: Option<http::HeaderValue>,
+}
+
+impl TraceContext {
+    /// Create a trace context from a `traceparent` of the form `{version}-{trace-id}-{parent-id}-{flags}`
+    pub fn new(traceparent: &str) -> Result<Self, Error> {
+        let invalid = || Error::Validation(format!("invalid traceparent {traceparent:?}"));
+        let parts = traceparent.split('-').collect::<Vec<_>>();
+        let [version, trace_id, parent_id, flags] = parts[..] else {
+            return Err(invalid());
+        };
+        let is_hex = |s: &str, len: usize| {
+            s.len() == len
+                && s.bytes()
+                    .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+        };
+        let all_zero = |s: &str| s.bytes().all(|b| b == b'0');
+        if !is_hex(version, 2)
+            || version == "ff"
+            || !is_hex(trace_id, 32)
+            || all_zero(trace_id)
+            || !is_hex(parent_id, 16)
+            || all_zero(parent_id)
+            || !is_hex(flags, 2)
+        {
+            return Err(invalid());
+        }
+        Ok(Self {
+            traceparent: traceparent.parse().map_err(|_| invalid())?,
+            tracestate: None,
+        })
+    }
+
+    /// Set the vendor specific `tracestate` propagated alongside the `traceparent`
+    pub fn with_tracestate(mut self, tracestate: &str) -> Result<Self, Error> {
+        let value = tracestate
+            .parse()
+            .map_err(|_| Error::Validation(format!("invalid tracestate {tracestate:?}")))?;
+        self.tracestate = Some(value);
+        Ok(self)
+    }
+
+    /// Set the trace context headers on a request, replacing any existing ones
+    pub fn apply<B>(&self, req: &mut http::Request<B>) {
+        let headers = req.headers_mut();
+        headers.insert("traceparent", self.traceparent.clone());
+        match &self.tracestate {
+            Some(state) => headers.insert("tracestate", state.clone()),
+            None => headers.remove("tracestate"),
+        };
+    }
 }
 
 // -------------------------------------------------------
@@ -63,7 +156,7 @@ impl Request {
         lp.populate_qp(&mut qp);
         let urlstr = qp.finish();
         let req = http::Request::get(urlstr);
-        req.body(vec![]).map_err(Error::BuildRequest)
+        self.build(req, vec![])
     }
 
     /// Watch a resource at a given version
@@ -75,7 +168,7 @@ impl Request {
         qp.append_pair("resourceVersion", ver);
         let urlstr = qp.finish();
         let req = http::Request::get(urlstr);
-        req.body(vec![]).map_err(Error::BuildRequest)
+        self.build(req, vec![])
     }
 
     /// Get a single instance
@@ -91,7 +184,7 @@ impl Request {
             form_urlencoded::Serializer::new(target).finish()
         };
         let req = http::Request::get(urlstr);
-        req.body(vec![]).map_err(Error::BuildRequest)
+        self.build(req, vec![])
     }
 
     /// Create an instance of a resource
@@ -102,7 +195,20 @@ impl Request {
         pp.populate_qp(&mut qp);
         let urlstr = qp.finish();
         let req = http::Request::post(urlstr).header(http::header::CONTENT_TYPE, JSON_MIME);
-        req.body(data).map_err(Error::BuildRequest)
+        self.build(req, data)
+    }
+
+    /// Create an instance of a dynamic resource, stripping server-managed fields first
+    ///
+    /// The object is normalized with [`DynamicObject::for_create`] before it is serialized,
+    /// so objects read back from the apiserver can be re-created as is.
+    pub fn create_normalized(
+        &self,
+        pp: &PostParams,
+        obj: &DynamicObject,
+    ) -> Result<http::Request<Vec<u8>>, Error> {
+        let data = serde_json::to_vec(&obj.clone().for_create()).map_err(Error::SerializeBody)?;
+        self.create(pp, data)
     }
 
     /// Delete an instance of a resource
@@ -113,7 +219,7 @@ impl Request {
         let urlstr = qp.finish();
         let body = serde_json::to_vec(&dp).map_err(Error::SerializeBody)?;
         let req = http::Request::delete(urlstr).header(http::header::CONTENT_TYPE, JSON_MIME);
-        req.body(body).map_err(Error::BuildRequest)
+        self.build(req, body)
     }
 
     /// Delete a collection of a resource
@@ -139,7 +245,7 @@ impl Request {
         };
 
         let req = http::Request::delete(urlstr).header(http::header::CONTENT_TYPE, JSON_MIME);
-        req.body(data).map_err(Error::BuildRequest)
+        self.build(req, data)
     }
 
     /// Patch an instance of a resource
@@ -158,11 +264,10 @@ impl Request {
         pp.populate_qp(&mut qp);
         let urlstr = qp.finish();
 
-        http::Request::patch(urlstr)
+        let req = http::Request::patch(urlstr)
             .header(http::header::ACCEPT, JSON_MIME)
-            .header(http::header::CONTENT_TYPE, patch.content_type())
-            .body(patch.serialize().map_err(Error::SerializeBody)?)
-            .map_err(Error::BuildRequest)
+            .header(http::header::CONTENT_TYPE, patch.content_type());
+        self.build(req, patch.serialize().map_err(Error::SerializeBody)?)
     }
 
     /// Replace an instance of a resource
@@ -180,7 +285,7 @@ impl Request {
         pp.populate_qp(&mut qp);
         let urlstr = qp.finish();
         let req = http::Request::put(urlstr).header(http::header::CONTENT_TYPE, JSON_MIME);
-        req.body(data).map_err(Error::BuildRequest)
+        self.build(req, data)
     }
 }
 
@@ -197,7 +302,7 @@ impl Request {
         let mut qp = form_urlencoded::Serializer::new(target);
         let urlstr = qp.finish();
         let req = http::Request::get(urlstr);
-        req.body(vec![]).map_err(Error::BuildRequest)
+        self.build(req, vec![])
     }
 
     /// Create an instance of the subresource
@@ -214,7 +319,7 @@ impl Request {
         pp.populate_qp(&mut qp);
         let urlstr = qp.finish();
         let req = http::Request::post(urlstr).header(http::header::CONTENT_TYPE, JSON_MIME);
-        req.body(data).map_err(Error::BuildRequest)
+        self.build(req, data)
     }
 
     /// Patch an instance of the subresource
@@ -232,11 +337,10 @@ impl Request {
         pp.populate_qp(&mut qp);
         let urlstr = qp.finish();
 
-        http::Request::patch(urlstr)
+        let req = http::Request::patch(urlstr)
             .header(http::header::ACCEPT, JSON_MIME)
-            .header(http::header::CONTENT_TYPE, patch.content_type())
-            .body(patch.serialize().map_err(Error::SerializeBody)?)
-            .map_err(Error::BuildRequest)
+            .header(http::header::CONTENT_TYPE, patch.content_type());
+        self.build(req, patch.serialize().map_err(Error::SerializeBody)?)
     }
 
     /// Replace an instance of the subresource
@@ -253,7 +357,7 @@ impl Request {
         pp.populate_qp(&mut qp);
         let urlstr = qp.finish();
         let req = http::Request::put(urlstr).header(http::header::CONTENT_TYPE, JSON_MIME);
-        req.body(data).map_err(Error::BuildRequest)
+        self.build(req, data)
     }
 }
 
@@ -277,7 +381,7 @@ impl Request {
         let req = http::Request::get(urlstr)
             .header(http::header::ACCEPT, JSON_METADATA_MIME)
             .header(http::header::CONTENT_TYPE, JSON_MIME);
-        req.body(vec![]).map_err(Error::BuildRequest)
+        self.build(req, vec![])
     }
 
     /// List a collection of metadata of a resource
@@ -291,7 +395,7 @@ impl Request {
             .header(http::header::ACCEPT, JSON_METADATA_LIST_MIME)
             .header(http::header::CONTENT_TYPE, JSON_MIME);
 
-        req.body(vec![]).map_err(Error::BuildRequest)
+        self.build(req, vec![])
     }
 
     /// Watch metadata of a resource at a given version
@@ -303,11 +407,10 @@ impl Request {
         qp.append_pair("resourceVersion", ver);
 
         let urlstr = qp.finish();
-        http::Request::get(urlstr)
+        let req = http::Request::get(urlstr)
             .header(http::header::ACCEPT, JSON_METADATA_MIME)
-            .header(http::header::CONTENT_TYPE, JSON_MIME)
-            .body(vec![])
-            .map_err(Error::BuildRequest)
+            .header(http::header::CONTENT_TYPE, JSON_MIME);
+        self.build(req, vec![])
     }
 
     /// Patch an instance of a resource and receive its metadata only
@@ -326,11 +429,10 @@ impl Request {
         pp.populate_qp(&mut qp);
         let urlstr = qp.finish();
 
-        http::Request::patch(urlstr)
+        let req = http::Request::patch(urlstr)
             .header(http::header::ACCEPT, JSON_METADATA_MIME)
-            .header(http::header::CONTENT_TYPE, patch.content_type())
-            .body(patch.serialize().map_err(Error::SerializeBody)?)
-            .map_err(Error::BuildRequest)
+            .header(http::header::CONTENT_TYPE, patch.content_type());
+        self.build(req, patch.serialize().map_err(Error::SerializeBody)?)
     }
 }
 
@@ -348,8 +450,10 @@ fn validate_name(name: &str) -> Result<(), Error> {
 #[cfg(test)]
 mod test {
     use crate::{
+        dynamic::{ApiResource, DynamicObject},
+        gvk::GroupVersionKind,
         params::{GetParams, PostParams, VersionMatch, WatchParams},
-        request::{Error, Request},
+        request::{Error, Request, TraceContext},
         resource::Resource,
     };
     use http::header;
@@ -363,6 +467,23 @@ mod test {
     // NB: stable requires >= 1.17
     use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1 as apiextsv1;
 
+    #[test]
+    fn create_normalized_strips_server_fields() {
+        let ar = ApiResource::from_gvk(&GroupVersionKind::gvk("clux.dev", "v1", "Foo"));
+        let mut obj = DynamicObject::new("foo", &ar).within("ns");
+        obj.metadata.resource_version = Some("123".into());
+        obj.metadata.uid = Some("8c6a1c5b-4a12-4d0b-9f51-3f3b0a0d2f6e".into());
+        let url = DynamicObject::url_path(&ar, Some("ns"));
+        let req = Request::new(url)
+            .create_normalized(&PostParams::default(), &obj)
+            .unwrap();
+        assert_eq!(req.uri(), "/apis/clux.dev/v1/namespaces/ns/foos?");
+        let body: serde_json::Value = serde_json::from_slice(req.body()).unwrap();
+        assert_eq!(body["metadata"]["name"], "foo");
+        assert!(body["metadata"].get("resourceVersion").is_none());
+        assert!(body["metadata"].get("uid").is_none());
+    }
+
     // TODO: fixturize these tests
     #[test]
     fn api_url_secret() {
@@ -828,4 +949,36 @@ mod test {
         let err = Request::new(url).watch(&wp, "").unwrap_err();
         assert!(format!("{err}").contains("timeout must be < 295s"));
     }
+
+    #[test]
+    fn trace_context_on_dynamic_get() {
+        let ar = ApiResource::from_gvk(&GroupVersionKind::gvk("clux.dev", "v1", "Foo"));
+        let url = DynamicObject::url_path(&ar, Some("ns"));
+        let ctx = TraceContext::new("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01")
+            .unwrap()
+            .with_tracestate("congo=t61rcWkgMzE")
+            .unwrap();
+        let request = Request::new(url).with_trace_context(ctx);
+        let req = request.get("bar", &GetParams::default()).unwrap();
+        assert_eq!(
+            req.headers().get("traceparent").unwrap(),
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"
+        );
+        assert_eq!(req.headers().get("tracestate").unwrap(), "congo=t61rcWkgMzE");
+        let req = request.list(&ListParams::default()).unwrap();
+        assert!(req.headers().contains_key("traceparent"));
+    }
+
+    #[test]
+    fn trace_context_rejects_malformed_traceparent() {
+        for tp in [
+            "",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+        ] {
+            assert!(matches!(TraceContext::new(tp), Err(Error::Validation(_))), "{tp}");
+        }
+    }
 }
@@ -85,7 +85,7 @@ impl Request {
 
         let urlstr = qp.finish();
         let req = http::Request::get(urlstr);
-        req.body(vec![]).map_err(Error::BuildRequest)
+        self.build(req, vec![])
     }
 }
 
@@ -119,7 +119,7 @@ impl Request {
         }))
         .map_err(Error::SerializeBody)?;
         let req = http::Request::post(urlstr).header(http::header::CONTENT_TYPE, JSON_MIME);
-        req.body(data).map_err(Error::BuildRequest)
+        self.build(req, data)
     }
 }
 
@@ -328,7 +328,7 @@ impl Request {
         ap.append_to_url_serializer(&mut qp);
 
         let req = http::Request::get(qp.finish());
-        req.body(vec![]).map_err(Error::BuildRequest)
+        self.build(req, vec![])
     }
 }
 
@@ -360,7 +360,7 @@ impl Request {
         }
 
         let req = http::Request::get(qp.finish());
-        req.body(vec![]).map_err(Error::BuildRequest)
+        self.build(req, vec![])
     }
 }
 
@@ -401,7 +401,7 @@ impl Request {
         );
 
         let req = http::Request::get(qp.finish());
-        req.body(vec![]).map_err(Error::BuildRequest)
+        self.build(req, vec![])
     }
 }
 
@@ -452,4 +452,20 @@ mod test {
             "/api/v1/namespaces/ns/pods/mypod/log?&sinceTime=2023-10-19T13%3A14%3A26Z" // cross-referenced with kubectl
         );
     }
+
+    #[cfg(feature = "ws")]
+    #[test]
+    fn attach_path_from_api_resource() {
+        use crate::{dynamic::DynamicObject, gvk::GroupVersionKind, subresource::AttachParams, ApiResource};
+        let ar = ApiResource::from_gvk(&GroupVersionKind::gvk("", "v1", "Pod"));
+        let url = DynamicObject::url_path(&ar, Some("ns"));
+        let ap = AttachParams::interactive_tty().container("app");
+        let req = Request::new(url).attach("mypod", &ap).unwrap();
+        assert_eq!(req.method(), "GET");
+        assert_eq!(req.uri().path(), "/api/v1/namespaces/ns/pods/mypod/attach");
+        assert_eq!(
+            req.uri().query().unwrap(),
+            "&stdin=true&stdout=true&tty=true&container=app"
+        );
+    }
 }